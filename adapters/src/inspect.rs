@@ -1,9 +1,18 @@
 use frame_support::pallet_prelude::Get;
-use frame_support::traits::fungible::Inspect as FungibleInspect;
-use frame_support::traits::fungibles::Inspect as FungiblesInspect;
+use frame_support::traits::fungible::{
+    Inspect as FungibleInspect, InspectHold as FungibleInspectHold, Mutate as FungibleMutate,
+    MutateHold as FungibleMutateHold, Transfer as FungibleTransfer,
+};
+use frame_support::traits::fungibles::{
+    Inspect as FungiblesInspect, InspectHold as FungiblesInspectHold, Mutate as FungiblesMutate,
+    MutateHold as FungiblesMutateHold, Transfer as FungiblesTransfer,
+};
 use frame_support::traits::tokens::{DepositConsequence, WithdrawConsequence};
+use sp_runtime::{DispatchError, DispatchResult};
 
-/// An adapter to use inspect functionality for both native and multi currency
+/// An adapter to use fungibles functionality for both native and multi currency.
+///
+/// Operations on `GetNativeCurrencyId` are dispatched to `NativeCurrency`, all other assets to `MultiCurrency`.
 pub struct MultiInspectAdapter<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId>(
     sp_std::marker::PhantomData<(
         AccountId,
@@ -87,3 +96,141 @@ where
         }
     }
 }
+
+impl<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId> FungiblesMutate<AccountId>
+    for MultiInspectAdapter<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId>
+where
+    AssetId: frame_support::traits::tokens::AssetId,
+    Balance: frame_support::traits::tokens::Balance,
+    NativeCurrency: FungibleMutate<AccountId, Balance = Balance>,
+    MultiCurrency: FungiblesMutate<AccountId, AssetId = AssetId, Balance = Balance>,
+    GetNativeCurrencyId: Get<AssetId>,
+{
+    fn mint_into(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DispatchResult {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::mint_into(who, amount)
+        } else {
+            MultiCurrency::mint_into(asset, who, amount)
+        }
+    }
+
+    fn burn_from(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::burn_from(who, amount)
+        } else {
+            MultiCurrency::burn_from(asset, who, amount)
+        }
+    }
+}
+
+impl<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId> FungiblesTransfer<AccountId>
+    for MultiInspectAdapter<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId>
+where
+    AssetId: frame_support::traits::tokens::AssetId,
+    Balance: frame_support::traits::tokens::Balance,
+    NativeCurrency: FungibleTransfer<AccountId, Balance = Balance>,
+    MultiCurrency: FungiblesTransfer<AccountId, AssetId = AssetId, Balance = Balance>,
+    GetNativeCurrencyId: Get<AssetId>,
+{
+    fn transfer(
+        asset: Self::AssetId,
+        source: &AccountId,
+        dest: &AccountId,
+        amount: Self::Balance,
+        keep_alive: bool,
+    ) -> Result<Self::Balance, DispatchError> {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::transfer(source, dest, amount, keep_alive)
+        } else {
+            MultiCurrency::transfer(asset, source, dest, amount, keep_alive)
+        }
+    }
+
+    fn deactivate(asset: Self::AssetId, amount: Self::Balance) {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::deactivate(amount)
+        } else {
+            MultiCurrency::deactivate(asset, amount)
+        }
+    }
+
+    fn reactivate(asset: Self::AssetId, amount: Self::Balance) {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::reactivate(amount)
+        } else {
+            MultiCurrency::reactivate(asset, amount)
+        }
+    }
+}
+
+impl<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId> FungiblesInspectHold<AccountId>
+    for MultiInspectAdapter<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId>
+where
+    AssetId: frame_support::traits::tokens::AssetId,
+    Balance: frame_support::traits::tokens::Balance,
+    NativeCurrency: FungibleInspectHold<AccountId, Balance = Balance>,
+    MultiCurrency: FungiblesInspectHold<AccountId, AssetId = AssetId, Balance = Balance>,
+    GetNativeCurrencyId: Get<AssetId>,
+{
+    fn balance_on_hold(asset: Self::AssetId, who: &AccountId) -> Self::Balance {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::balance_on_hold(who)
+        } else {
+            MultiCurrency::balance_on_hold(asset, who)
+        }
+    }
+
+    fn can_hold(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> bool {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::can_hold(who, amount)
+        } else {
+            MultiCurrency::can_hold(asset, who, amount)
+        }
+    }
+}
+
+impl<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId> FungiblesMutateHold<AccountId>
+    for MultiInspectAdapter<AccountId, AssetId, Balance, NativeCurrency, MultiCurrency, GetNativeCurrencyId>
+where
+    AssetId: frame_support::traits::tokens::AssetId,
+    Balance: frame_support::traits::tokens::Balance,
+    NativeCurrency: FungibleMutateHold<AccountId, Balance = Balance>,
+    MultiCurrency: FungiblesMutateHold<AccountId, AssetId = AssetId, Balance = Balance>,
+    GetNativeCurrencyId: Get<AssetId>,
+{
+    fn hold(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DispatchResult {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::hold(who, amount)
+        } else {
+            MultiCurrency::hold(asset, who, amount)
+        }
+    }
+
+    fn release(
+        asset: Self::AssetId,
+        who: &AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+    ) -> Result<Self::Balance, DispatchError> {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::release(who, amount, best_effort)
+        } else {
+            MultiCurrency::release(asset, who, amount, best_effort)
+        }
+    }
+
+    fn transfer_held(
+        asset: Self::AssetId,
+        source: &AccountId,
+        dest: &AccountId,
+        amount: Self::Balance,
+        best_effort: bool,
+        on_hold: bool,
+    ) -> Result<Self::Balance, DispatchError> {
+        if GetNativeCurrencyId::get() == asset {
+            NativeCurrency::transfer_held(source, dest, amount, best_effort, on_hold)
+        } else {
+            MultiCurrency::transfer_held(asset, source, dest, amount, best_effort, on_hold)
+        }
+    }
+}
@@ -23,14 +23,15 @@ use pallet_transaction_multi_payment::DepositFee;
 use polkadot_xcm::latest::prelude::*;
 use sp_runtime::traits::Get;
 use sp_runtime::{
-    traits::{AtLeast32BitUnsigned, Convert, Saturating, Zero},
+    traits::{AtLeast32BitUnsigned, Convert, Zero},
     FixedPointNumber, FixedPointOperand, SaturatedConversion,
 };
-use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData};
+use sp_std::{marker::PhantomData, vec::Vec};
 use xcm_builder::TakeRevenue;
 use xcm_executor::{traits::WeightTrader, Assets};
 
 pub mod inspect;
+pub mod swap;
 
 #[cfg(test)]
 mod tests;
@@ -39,7 +40,8 @@ mod tests;
 ///
 /// It uses `WeightToFee` in combination with a `NativePriceOracle` to set the right price for weight.
 /// Keeps track of the assets used to pay for weight and can refund them one by one (interface only
-/// allows returning one asset per refund). Will pass any remaining assets on `Drop` to
+/// allows returning one asset per refund). Refunds are last-in-first-out: the asset most recently
+/// used to buy weight is refunded first. Will pass any remaining assets on `Drop` to
 /// `TakeRevenue`.
 pub struct MultiCurrencyTrader<
     AssetId,
@@ -51,7 +53,9 @@ pub struct MultiCurrencyTrader<
     Revenue: TakeRevenue,
 > {
     weight: Weight,
-    paid_assets: BTreeMap<(MultiLocation, Price), u128>,
+    /// Assets paid for weight with the price they were paid at, the paid amount and the weight
+    /// still refundable from it, ordered by the last purchase.
+    paid_assets: Vec<(MultiLocation, Price, u128, Weight)>,
     _phantom: PhantomData<(
         AssetId,
         Balance,
@@ -135,39 +139,51 @@ impl<
         let required = (Concrete(asset_loc), amount).into();
         let unused = payment.checked_sub(required).map_err(|_| XcmError::TooExpensive)?;
         self.weight = self.weight.saturating_add(weight);
-        let key = (asset_loc, price);
-        match self.paid_assets.get_mut(&key) {
-            Some(v) => v.saturating_accrue(amount),
-            None => {
-                self.paid_assets.insert(key, amount);
+        // move the asset to the end to be refunded first
+        let (paid, bought) = match self
+            .paid_assets
+            .iter()
+            .position(|(loc, p, _, _)| *loc == asset_loc && *p == price)
+        {
+            Some(index) => {
+                let (_, _, paid, bought) = self.paid_assets.remove(index);
+                (paid.saturating_add(amount), bought.saturating_add(weight))
             }
-        }
+            None => (amount, weight),
+        };
+        self.paid_assets.push((asset_loc, price, paid, bought));
         Ok(unused)
     }
 
-    /// Will refund up to `weight` from the first asset tracked by the trader.
+    /// Will refund up to `weight` from the asset most recently used to buy weight.
+    ///
+    /// Only the weight bought with that asset is refunded, the rest of `weight` is refunded from
+    /// the next asset by the next call.
     fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
         log::trace!(
             target: "xcm::weight", "MultiCurrencyTrader::refund_weight weight: {:?}, paid_assets: {:?}",
             weight, self.paid_assets
         );
-        let weight = weight.min(self.weight);
+        let index = self
+            .paid_assets
+            .iter()
+            .rposition(|(_, _, _, bought)| !bought.is_zero())?;
+        let (asset_loc, price, amount, bought) = &mut self.paid_assets[index];
+
+        let weight = weight.min(*bought).min(self.weight);
+        *bought -= weight; // Will not underflow because of `min()` above.
         self.weight -= weight; // Will not underflow because of `min()` above.
+
         let fee = ConvertWeightToFee::weight_to_fee(&weight);
-        if let Some(((asset_loc, price), amount)) = self.paid_assets.iter_mut().next() {
-            let converted_fee: u128 = price.saturating_mul_int(fee).saturated_into();
-            let refund = converted_fee.min(*amount);
-            *amount -= refund; // Will not underflow because of `min()` above.
-
-            let refund_asset = *asset_loc;
-            if amount.is_zero() {
-                let key = (*asset_loc, *price);
-                self.paid_assets.remove(&key);
-            }
-            Some((Concrete(refund_asset), refund).into())
-        } else {
-            None
+        let converted_fee: u128 = price.saturating_mul_int(fee).saturated_into();
+        let refund = converted_fee.min(*amount);
+        *amount -= refund; // Will not underflow because of `min()` above.
+
+        let refund_asset = *asset_loc;
+        if amount.is_zero() {
+            self.paid_assets.remove(index);
         }
+        Some((Concrete(refund_asset), refund).into())
     }
 }
 
@@ -193,7 +209,7 @@ impl<
     >
 {
     fn drop(&mut self) {
-        for ((asset_loc, _), amount) in self.paid_assets.iter() {
+        for (asset_loc, _, amount, _) in self.paid_assets.iter() {
             Revenue::take_revenue((*asset_loc, *amount).into());
         }
     }
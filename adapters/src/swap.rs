@@ -0,0 +1,268 @@
+// This file is part of hydradx-adapters.
+
+// Copyright (C) 2022  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::MultiCurrencyTrader;
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use frame_support::weights::{Weight, WeightToFee};
+use hydradx_traits::{router::RouterSell, NativePriceOracle};
+use pallet_transaction_multi_payment::DepositFee;
+use polkadot_xcm::latest::prelude::*;
+use sp_runtime::traits::{Convert, Get, Saturating, Zero};
+use sp_runtime::{ArithmeticError, FixedPointNumber, FixedPointOperand, Permill};
+use sp_std::{marker::PhantomData, vec::Vec};
+use xcm_executor::{traits::WeightTrader, Assets};
+
+/// Weight trader that accepts multiple assets as weight fee payment and swaps them to native asset.
+///
+/// Weight is bought at the prices of `AcceptedCurrencyPrices` the same way as with
+/// `MultiCurrencyTrader`. On `Drop`, the collected assets are deposited to `FeeReceiver` via
+/// `DepositFee` and non-native assets are sold for native asset via `Swap`. The sale must return
+/// at least the amount determined by the price used to buy weight minus `MaxSlippage`.
+///
+/// The weight of the sale, `Swap::sell_weight`, is charged when a non-native asset is first used
+/// to buy weight and is not refundable.
+///
+/// If the sale fails, its changes are rolled back and the collected asset stays with
+/// `FeeReceiver`, which is the same outcome as `MultiCurrencyTrader` with `ToFeeReceiver` revenue.
+///
+/// Note: `Deposit` should not buffer the deposited fees for swapping, e.g. `DepositAndSwapFees`,
+/// because the deposited fees are swapped right away.
+#[allow(clippy::type_complexity)]
+pub struct SwappingMultiCurrencyTrader<
+    AccountId,
+    AssetId: PartialEq + Copy,
+    Balance: FixedPointOperand + TryInto<u128>,
+    Price: FixedPointNumber,
+    ConvertWeightToFee: WeightToFee<Balance = Balance>,
+    AcceptedCurrencyPrices: NativePriceOracle<AssetId, Price>,
+    ConvertCurrency: Convert<MultiAsset, Option<AssetId>>,
+    Swap: RouterSell<AccountId, AssetId, Balance>,
+    Deposit: DepositFee<AccountId, AssetId, Balance>,
+    FeeReceiver: Get<AccountId>,
+    NativeAssetId: Get<AssetId>,
+    MaxSlippage: Get<Permill>,
+> {
+    inner:
+        MultiCurrencyTrader<AssetId, Balance, Price, ConvertWeightToFee, AcceptedCurrencyPrices, ConvertCurrency, ()>,
+    /// Assets whose sale was paid for.
+    swap_paid: Vec<MultiLocation>,
+    _phantom: PhantomData<(AccountId, Swap, Deposit, FeeReceiver, NativeAssetId, MaxSlippage)>,
+}
+
+impl<
+        AccountId,
+        AssetId: PartialEq + Copy,
+        Balance: FixedPointOperand + TryInto<u128>,
+        Price: FixedPointNumber,
+        ConvertWeightToFee: WeightToFee<Balance = Balance>,
+        AcceptedCurrencyPrices: NativePriceOracle<AssetId, Price>,
+        ConvertCurrency: Convert<MultiAsset, Option<AssetId>>,
+        Swap: RouterSell<AccountId, AssetId, Balance>,
+        Deposit: DepositFee<AccountId, AssetId, Balance>,
+        FeeReceiver: Get<AccountId>,
+        NativeAssetId: Get<AssetId>,
+        MaxSlippage: Get<Permill>,
+    >
+    SwappingMultiCurrencyTrader<
+        AccountId,
+        AssetId,
+        Balance,
+        Price,
+        ConvertWeightToFee,
+        AcceptedCurrencyPrices,
+        ConvertCurrency,
+        Swap,
+        Deposit,
+        FeeReceiver,
+        NativeAssetId,
+        MaxSlippage,
+    >
+{
+    /// Minimum amount of native asset expected for `amount` of an asset bought at `price`.
+    fn min_amount_out(amount: u128, price: Price) -> Option<Balance> {
+        let expected = price.reciprocal()?.checked_mul_int(amount)?;
+        let min_amount_out = Permill::one().saturating_sub(MaxSlippage::get()).mul_floor(expected);
+        Balance::try_from(min_amount_out).ok()
+    }
+
+    /// Weight of selling the first asset in `payment` if it is a non-native asset whose sale was not
+    /// paid for yet.
+    fn unpaid_swap_weight(&self, payment: &Assets) -> Weight {
+        let asset = match payment.fungible_assets_iter().next() {
+            Some(asset) => asset,
+            None => return Weight::zero(),
+        };
+        let is_unpaid = match asset.id {
+            Concrete(location) => !self.swap_paid.contains(&location),
+            _ => false,
+        };
+        let is_native = ConvertCurrency::convert(asset).map_or(false, |asset_id| asset_id == NativeAssetId::get());
+
+        if is_unpaid && !is_native {
+            Swap::sell_weight()
+        } else {
+            Weight::zero()
+        }
+    }
+
+    /// Deposit `amount` of the asset at `asset_loc` to `FeeReceiver` and sell it for native asset.
+    fn deposit_and_swap(asset_loc: MultiLocation, price: Price, amount: u128) {
+        let asset_id = match ConvertCurrency::convert((asset_loc, amount).into()) {
+            Some(asset_id) => asset_id,
+            None => {
+                log::trace!(target: "xcm::weight", "Unknown asset {:?} was used to buy weight.", asset_loc);
+                return;
+            }
+        };
+        let amount_in = match Balance::try_from(amount) {
+            Ok(amount_in) => amount_in,
+            Err(_) => {
+                log::trace!(target: "xcm::weight", "Fee amount {:?} overflows balance.", amount);
+                return;
+            }
+        };
+
+        let receiver = FeeReceiver::get();
+        if let Err(e) = Deposit::deposit_fee(&receiver, asset_id, amount_in) {
+            log::trace!(target: "xcm::weight", "Could not deposit fee: {:?}", e);
+            return;
+        }
+
+        let native_asset = NativeAssetId::get();
+        if asset_id == native_asset {
+            return;
+        }
+
+        let result = with_transaction(|| {
+            let result = Self::min_amount_out(amount, price)
+                .ok_or_else(|| ArithmeticError::Overflow.into())
+                .and_then(|min_amount_out| Swap::sell(&receiver, asset_id, native_asset, amount_in, min_amount_out));
+            match result {
+                Ok(()) => TransactionOutcome::Commit(Ok(())),
+                Err(e) => TransactionOutcome::Rollback(Err(e)),
+            }
+        });
+        if let Err(e) = result {
+            // the fee stays with the fee receiver in the asset it was paid in
+            log::trace!(target: "xcm::weight", "Could not swap fee to native asset: {:?}", e);
+        }
+    }
+}
+
+impl<
+        AccountId,
+        AssetId: PartialEq + Copy,
+        Balance: FixedPointOperand + TryInto<u128>,
+        Price: FixedPointNumber,
+        ConvertWeightToFee: WeightToFee<Balance = Balance>,
+        AcceptedCurrencyPrices: NativePriceOracle<AssetId, Price>,
+        ConvertCurrency: Convert<MultiAsset, Option<AssetId>>,
+        Swap: RouterSell<AccountId, AssetId, Balance>,
+        Deposit: DepositFee<AccountId, AssetId, Balance>,
+        FeeReceiver: Get<AccountId>,
+        NativeAssetId: Get<AssetId>,
+        MaxSlippage: Get<Permill>,
+    > WeightTrader
+    for SwappingMultiCurrencyTrader<
+        AccountId,
+        AssetId,
+        Balance,
+        Price,
+        ConvertWeightToFee,
+        AcceptedCurrencyPrices,
+        ConvertCurrency,
+        Swap,
+        Deposit,
+        FeeReceiver,
+        NativeAssetId,
+        MaxSlippage,
+    >
+{
+    fn new() -> Self {
+        Self {
+            inner: MultiCurrencyTrader::new(),
+            swap_paid: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Will try to buy weight with the first asset in `payment` at the price determined by
+    /// `AcceptedCurrencyPrices`.
+    ///
+    /// The weight of selling the asset is bought as well if it is a non-native asset used for the
+    /// first time.
+    fn buy_weight(&mut self, weight: Weight, payment: Assets) -> Result<Assets, XcmError> {
+        let swap_weight = self.unpaid_swap_weight(&payment);
+        let unused = self.inner.buy_weight(weight.saturating_add(swap_weight), payment)?;
+
+        if !swap_weight.is_zero() {
+            // the sale happens in any case, so its weight is not refundable
+            self.inner.weight = self.inner.weight.saturating_sub(swap_weight);
+            if let Some((asset_loc, _, _, bought)) = self.inner.paid_assets.last_mut() {
+                *bought = bought.saturating_sub(swap_weight);
+                self.swap_paid.push(*asset_loc);
+            }
+        }
+
+        Ok(unused)
+    }
+
+    /// Will refund up to `weight` from the first asset tracked by the trader.
+    fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
+        self.inner.refund_weight(weight)
+    }
+}
+
+/// We implement `Drop` so that when the weight trader is dropped at the end of XCM execution, the
+/// collected fees are deposited to the fee receiver and swapped to native asset.
+impl<
+        AccountId,
+        AssetId: PartialEq + Copy,
+        Balance: FixedPointOperand + TryInto<u128>,
+        Price: FixedPointNumber,
+        ConvertWeightToFee: WeightToFee<Balance = Balance>,
+        AcceptedCurrencyPrices: NativePriceOracle<AssetId, Price>,
+        ConvertCurrency: Convert<MultiAsset, Option<AssetId>>,
+        Swap: RouterSell<AccountId, AssetId, Balance>,
+        Deposit: DepositFee<AccountId, AssetId, Balance>,
+        FeeReceiver: Get<AccountId>,
+        NativeAssetId: Get<AssetId>,
+        MaxSlippage: Get<Permill>,
+    > Drop
+    for SwappingMultiCurrencyTrader<
+        AccountId,
+        AssetId,
+        Balance,
+        Price,
+        ConvertWeightToFee,
+        AcceptedCurrencyPrices,
+        ConvertCurrency,
+        Swap,
+        Deposit,
+        FeeReceiver,
+        NativeAssetId,
+        MaxSlippage,
+    >
+{
+    fn drop(&mut self) {
+        for (asset_loc, price, amount, _) in sp_std::mem::take(&mut self.inner.paid_assets) {
+            if !amount.is_zero() {
+                Self::deposit_and_swap(asset_loc, price, amount);
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// This file is part of hydradx-adapters.
+
+// Copyright (C) 2022  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::inspect::MultiInspectAdapter;
+use frame_support::traits::fungibles::{Inspect, InspectHold, Mutate, MutateHold, Transfer};
+use frame_support::traits::{Everything, GenesisBuild, Nothing};
+use frame_support::{assert_noop, assert_ok, parameter_types};
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup, One},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+type AccountId = u64;
+type AssetId = u32;
+type Balance = u128;
+type Amount = i128;
+
+const ALICE: AccountId = 1;
+const BOB: AccountId = 2;
+
+const HDX: AssetId = 0;
+const DOT: AssetId = 1;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+     Block = Block,
+     NodeBlock = Block,
+     UncheckedExtrinsic = UncheckedExtrinsic,
+     {
+         System: frame_system,
+         Balances: pallet_balances,
+         Tokens: orml_tokens,
+     }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const ExistentialDeposit: Balance = 2;
+    pub const NativeCurrencyId: AssetId = HDX;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = frame_system::Pallet<Test>;
+    type WeightInfo = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: AssetId| -> Balance {
+        One::one()
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type Amount = Amount;
+    type CurrencyId = AssetId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type MaxLocks = ();
+    type DustRemovalWhitelist = Nothing;
+    type ReserveIdentifier = ();
+    type MaxReserves = ();
+    type CurrencyHooks = ();
+}
+
+type Adapter = MultiInspectAdapter<AccountId, AssetId, Balance, Balances, Tokens, NativeCurrencyId>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(ALICE, 1_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    orml_tokens::GenesisConfig::<Test> {
+        balances: vec![(ALICE, DOT, 2_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    t.into()
+}
+
+#[test]
+fn mint_and_burn_should_dispatch_by_asset_id() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Adapter::mint_into(HDX, &BOB, 100));
+        assert_ok!(Adapter::mint_into(DOT, &BOB, 200));
+
+        assert_eq!(Balances::free_balance(BOB), 100);
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 200);
+        assert_eq!(Adapter::total_issuance(HDX), 1_100);
+        assert_eq!(Adapter::total_issuance(DOT), 2_200);
+
+        assert_eq!(Adapter::burn_from(HDX, &BOB, 40), Ok(40));
+        assert_eq!(Adapter::burn_from(DOT, &BOB, 50), Ok(50));
+
+        assert_eq!(Balances::free_balance(BOB), 60);
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 150);
+    });
+}
+
+#[test]
+fn transfer_should_dispatch_by_asset_id() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Adapter::transfer(HDX, &ALICE, &BOB, 100, true), Ok(100));
+        assert_eq!(Adapter::transfer(DOT, &ALICE, &BOB, 200, true), Ok(200));
+
+        assert_eq!(Balances::free_balance(ALICE), 900);
+        assert_eq!(Balances::free_balance(BOB), 100);
+        assert_eq!(Tokens::free_balance(DOT, &ALICE), 1_800);
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 200);
+    });
+}
+
+#[test]
+fn transfer_should_fail_when_balance_is_insufficient() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Adapter::transfer(HDX, &BOB, &ALICE, 100, false),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            Adapter::transfer(DOT, &BOB, &ALICE, 100, false),
+            orml_tokens::Error::<Test>::BalanceTooLow
+        );
+    });
+}
+
+#[test]
+fn hold_and_release_should_dispatch_by_asset_id() {
+    new_test_ext().execute_with(|| {
+        assert!(Adapter::can_hold(HDX, &ALICE, 300));
+        assert!(Adapter::can_hold(DOT, &ALICE, 400));
+        assert!(!Adapter::can_hold(DOT, &BOB, 1));
+
+        assert_ok!(Adapter::hold(HDX, &ALICE, 300));
+        assert_ok!(Adapter::hold(DOT, &ALICE, 400));
+
+        assert_eq!(Adapter::balance_on_hold(HDX, &ALICE), 300);
+        assert_eq!(Adapter::balance_on_hold(DOT, &ALICE), 400);
+        assert_eq!(Balances::reserved_balance(ALICE), 300);
+        assert_eq!(Tokens::accounts(ALICE, DOT).reserved, 400);
+
+        assert_eq!(Adapter::release(HDX, &ALICE, 100, false), Ok(100));
+        assert_eq!(Adapter::release(DOT, &ALICE, 100, false), Ok(100));
+
+        assert_eq!(Adapter::balance_on_hold(HDX, &ALICE), 200);
+        assert_eq!(Adapter::balance_on_hold(DOT, &ALICE), 300);
+    });
+}
+
+#[test]
+fn transfer_held_should_dispatch_by_asset_id() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Adapter::hold(HDX, &ALICE, 300));
+        assert_ok!(Adapter::hold(DOT, &ALICE, 400));
+
+        assert_eq!(Adapter::transfer_held(HDX, &ALICE, &BOB, 100, false, false), Ok(100));
+        assert_eq!(Adapter::transfer_held(DOT, &ALICE, &BOB, 200, false, false), Ok(200));
+
+        assert_eq!(Adapter::balance_on_hold(HDX, &ALICE), 200);
+        assert_eq!(Adapter::balance_on_hold(DOT, &ALICE), 200);
+        assert_eq!(Balances::free_balance(BOB), 100);
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 200);
+    });
+}
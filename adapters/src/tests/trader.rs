@@ -0,0 +1,792 @@
+// This file is part of hydradx-adapters.
+
+// Copyright (C) 2022  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::swap::SwappingMultiCurrencyTrader;
+use crate::*;
+use codec::{Decode, Encode};
+use frame_support::{parameter_types, weights::IdentityFee, BoundedVec};
+use hydradx_traits::router::RouterSell;
+use sp_runtime::{traits::One, DispatchError, DispatchResult, FixedU128, Permill};
+use sp_std::cell::RefCell;
+use sp_std::collections::btree_set::BTreeSet;
+
+type AccountId = u32;
+type AssetId = u32;
+type Balance = u128;
+type Price = FixedU128;
+
+const CORE_ASSET_ID: AssetId = 0;
+const TEST_ASSET_ID: AssetId = 123;
+const CHEAP_ASSET_ID: AssetId = 420;
+const OVERFLOW_ASSET_ID: AssetId = 1_000;
+
+/// Mock price oracle which returns prices for the hard-coded assets.
+struct MockOracle;
+impl NativePriceOracle<AssetId, Price> for MockOracle {
+    fn price(currency: AssetId) -> Option<Price> {
+        match currency {
+            CORE_ASSET_ID => Some(Price::one()),
+            TEST_ASSET_ID => Some(Price::from_float(0.5)),
+            CHEAP_ASSET_ID => Some(Price::saturating_from_integer(4)),
+            OVERFLOW_ASSET_ID => Some(Price::saturating_from_integer(2_147_483_647)),
+            _ => None,
+        }
+    }
+}
+
+struct MockConvert;
+impl Convert<AssetId, Option<MultiLocation>> for MockConvert {
+    fn convert(id: AssetId) -> Option<MultiLocation> {
+        match id {
+            CORE_ASSET_ID | TEST_ASSET_ID | CHEAP_ASSET_ID | OVERFLOW_ASSET_ID => {
+                let junction = Junction::from(BoundedVec::try_from(id.encode()).unwrap());
+                Some(polkadot_xcm::v3::MultiLocation::new(0, X1(junction)))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Convert<MultiLocation, Option<AssetId>> for MockConvert {
+    fn convert(location: MultiLocation) -> Option<AssetId> {
+        match location {
+            MultiLocation {
+                parents: 0,
+                interior: X1(GeneralKey { data, .. }),
+            } => {
+                if let Ok(currency_id) = AssetId::decode(&mut &data[..]) {
+                    // we currently have only one native asset
+                    match currency_id {
+                        CORE_ASSET_ID | TEST_ASSET_ID | CHEAP_ASSET_ID | OVERFLOW_ASSET_ID => Some(currency_id),
+                        _ => None,
+                    }
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Convert<MultiAsset, Option<AssetId>> for MockConvert {
+    fn convert(asset: MultiAsset) -> Option<AssetId> {
+        if let MultiAsset {
+            id: Concrete(location), ..
+        } = asset
+        {
+            Self::convert(location)
+        } else {
+            None
+        }
+    }
+}
+
+thread_local! {
+    pub static TAKEN_REVENUE: RefCell<BTreeSet<MultiAsset>> = RefCell::new(BTreeSet::new());
+    pub static EXPECTED_REVENUE: RefCell<BTreeSet<MultiAsset>> = RefCell::new(BTreeSet::new());
+}
+
+struct ExpectRevenue;
+impl ExpectRevenue {
+    /// Register an asset to be expected.
+    fn register_expected_asset(asset: MultiAsset) {
+        EXPECTED_REVENUE.with(|e| e.borrow_mut().insert(asset));
+    }
+
+    /// Check the taken revenue contains all expected assets.
+    ///
+    /// Note: Will not notice if extra assets were taken (that were not expected).
+    fn expect_revenue() {
+        EXPECTED_REVENUE.with(|e| {
+            let expected = e.borrow();
+            for asset in expected.iter() {
+                assert!(TAKEN_REVENUE.with(|t| t.borrow().contains(dbg!(asset))));
+            }
+        });
+    }
+
+    /// Expect there to be no tracked revenue.
+    fn expect_no_revenue() {
+        assert!(
+            TAKEN_REVENUE.with(|t| t.borrow().is_empty()),
+            "There should be no revenue taken."
+        );
+    }
+
+    /// Reset the global mutable state.
+    fn reset() {
+        TAKEN_REVENUE.with(|t| *t.borrow_mut() = BTreeSet::new());
+        EXPECTED_REVENUE.with(|e| *e.borrow_mut() = BTreeSet::new());
+    }
+}
+
+impl TakeRevenue for ExpectRevenue {
+    fn take_revenue(asset: MultiAsset) {
+        TAKEN_REVENUE.with(|t| t.borrow_mut().insert(asset));
+    }
+}
+
+thread_local! {
+    pub static EXPECTED_DEPOSITS: RefCell<BTreeSet<(AccountId, AssetId, Balance)>> = RefCell::new(BTreeSet::new());
+}
+
+struct ExpectDeposit;
+impl ExpectDeposit {
+    /// Register an asset to be expected. The `DepositFee` implementation will panic if it receives
+    /// an unexpected asset.
+    fn register_expected_fee(who: AccountId, asset: AssetId, amount: Balance) {
+        EXPECTED_DEPOSITS.with(|e| e.borrow_mut().insert((who, asset, amount)));
+    }
+
+    /// Reset the global mutable state.
+    fn reset() {
+        EXPECTED_DEPOSITS.with(|e| *e.borrow_mut() = BTreeSet::new());
+    }
+}
+
+impl DepositFee<AccountId, AssetId, Balance> for ExpectDeposit {
+    fn deposit_fee(who: &AccountId, asset: AssetId, amount: Balance) -> DispatchResult {
+        log::trace!("Depositing {} of {} to {}", amount, asset, who);
+        assert!(
+            EXPECTED_DEPOSITS.with(|e| e.borrow_mut().remove(&(*who, asset, amount))),
+            "Unexpected combination of receiver and fee {:?} deposited that was not expected.",
+            (*who, asset, amount)
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn can_buy_weight() {
+    ExpectRevenue::reset();
+    type Trader =
+        MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ExpectRevenue>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+    let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+    let cheap_id = MockConvert::convert(CHEAP_ASSET_ID).unwrap();
+
+    {
+        let mut trader = Trader::new();
+
+        let core_payment: MultiAsset = (Concrete(core_id), 1_000_000).into();
+        let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), core_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == weight")
+            .is_empty());
+        ExpectRevenue::register_expected_asset(core_payment);
+
+        let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+        let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), test_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == 0.5 * weight")
+            .is_empty());
+        ExpectRevenue::register_expected_asset(test_payment);
+
+        let cheap_payment: MultiAsset = (Concrete(cheap_id), 4_000_000).into();
+        let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), cheap_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == 4 * weight")
+            .is_empty());
+        ExpectRevenue::register_expected_asset(cheap_payment);
+    }
+    ExpectRevenue::expect_revenue();
+}
+
+#[test]
+fn can_buy_twice() {
+    ExpectRevenue::reset();
+    type Trader =
+        MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ExpectRevenue>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+
+    {
+        let mut trader = Trader::new();
+
+        let payment1: MultiAsset = (Concrete(core_id), 1_000_000).into();
+        let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), payment1.into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == weight")
+            .is_empty());
+        let payment2: MultiAsset = (Concrete(core_id), 1_000_000).into();
+        let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), payment2.into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == weight")
+            .is_empty());
+        let total_payment: MultiAsset = (Concrete(core_id), 2_000_000).into();
+        ExpectRevenue::register_expected_asset(total_payment);
+    }
+    ExpectRevenue::expect_revenue();
+}
+
+#[test]
+fn cannot_buy_with_too_few_tokens() {
+    type Trader = MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ()>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+
+    let mut trader = Trader::new();
+
+    let payment: MultiAsset = (Concrete(core_id), 69).into();
+    let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), payment.into()));
+    assert_eq!(res, Err(XcmError::TooExpensive));
+}
+
+#[test]
+fn cannot_buy_with_unknown_token() {
+    type Trader = MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ()>;
+
+    let unknown_token = Junction::from(BoundedVec::try_from(9876u32.encode()).unwrap());
+
+    let mut trader = Trader::new();
+    let payment: MultiAsset = (Concrete(unknown_token.into()), 1_000_000).into();
+    let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), payment.into()));
+    assert_eq!(res, Err(XcmError::AssetNotFound));
+}
+
+#[test]
+fn cannot_buy_with_non_fungible() {
+    type Trader = MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ()>;
+
+    let unknown_token = Junction::from(BoundedVec::try_from(9876u32.encode()).unwrap());
+
+    let mut trader = Trader::new();
+    let payment: MultiAsset = (Concrete(unknown_token.into()), NonFungible(AssetInstance::Undefined)).into();
+    let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), payment.into()));
+    assert_eq!(res, Err(XcmError::AssetNotFound));
+}
+
+#[test]
+fn overflow_errors() {
+    use frame_support::traits::ConstU128;
+    use frame_support::weights::ConstantMultiplier;
+
+    type Trader = MultiCurrencyTrader<
+        AssetId,
+        Balance,
+        Price,
+        ConstantMultiplier<u128, ConstU128<{ Balance::MAX }>>,
+        MockOracle,
+        MockConvert,
+        (),
+    >;
+
+    let overflow_id = MockConvert::convert(OVERFLOW_ASSET_ID).unwrap();
+
+    let mut trader = Trader::new();
+
+    let amount = 1_000;
+    let payment: MultiAsset = (Concrete(overflow_id), amount).into();
+    let weight = Weight::from_ref_time(1_000);
+    let res = dbg!(trader.buy_weight(weight, payment.into()));
+    assert_eq!(res, Err(XcmError::Overflow));
+}
+
+#[test]
+fn refunds_first_asset_completely() {
+    ExpectRevenue::reset();
+
+    type Trader =
+        MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ExpectRevenue>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+
+    {
+        let mut trader = Trader::new();
+
+        let weight = Weight::from_ref_time(1_000_000);
+        let tokens = 1_000_000;
+        let core_payment: MultiAsset = (Concrete(core_id), tokens).into();
+        let res = dbg!(trader.buy_weight(weight, core_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == weight")
+            .is_empty());
+        assert_eq!(trader.refund_weight(weight), Some(core_payment));
+    }
+    ExpectRevenue::expect_no_revenue();
+}
+
+#[test]
+fn does_not_refund_if_empty() {
+    type Trader = MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ()>;
+
+    let mut trader = Trader::new();
+    assert_eq!(trader.refund_weight(Weight::from_ref_time(100)), None);
+}
+
+#[test]
+fn needs_multiple_refunds_for_multiple_currencies() {
+    ExpectRevenue::reset();
+
+    type Trader =
+        MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ExpectRevenue>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+    let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+    {
+        let mut trader = Trader::new();
+
+        let weight = Weight::from_ref_time(1_000_000);
+        let core_payment: MultiAsset = (Concrete(core_id), 1_000_000).into();
+        let res = dbg!(trader.buy_weight(weight, core_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == weight")
+            .is_empty());
+
+        let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+        let res = dbg!(trader.buy_weight(weight, test_payment.clone().into()));
+        assert!(res
+            .expect("buy_weight should succeed because payment == 0.5 * weight")
+            .is_empty());
+
+        // last in, first out
+        assert_eq!(trader.refund_weight(weight), Some(test_payment));
+        assert_eq!(trader.refund_weight(weight), Some(core_payment));
+    }
+    ExpectRevenue::expect_no_revenue();
+}
+
+#[test]
+fn refunds_most_recently_used_asset_first() {
+    ExpectRevenue::reset();
+
+    type Trader =
+        MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ExpectRevenue>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+    let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+    {
+        let mut trader = Trader::new();
+
+        let weight = Weight::from_ref_time(1_000_000);
+        let core_payment: MultiAsset = (Concrete(core_id), 1_000_000).into();
+        let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+        assert_ok_empty(trader.buy_weight(weight, core_payment.into()));
+        assert_ok_empty(trader.buy_weight(weight, test_payment.clone().into()));
+        assert_ok_empty(trader.buy_weight(weight, (Concrete(core_id), 1_000_000).into()));
+
+        // core asset was used last so it is refunded first, in full before moving on
+        assert_eq!(
+            trader.refund_weight(weight),
+            Some((Concrete(core_id), 1_000_000).into())
+        );
+        assert_eq!(
+            trader.refund_weight(weight),
+            Some((Concrete(core_id), 1_000_000).into())
+        );
+        assert_eq!(trader.refund_weight(weight), Some(test_payment));
+        assert_eq!(trader.refund_weight(weight), None);
+    }
+    ExpectRevenue::expect_no_revenue();
+}
+
+fn assert_ok_empty(res: Result<Assets, XcmError>) {
+    assert!(res.expect("buy_weight should succeed").is_empty());
+}
+
+#[test]
+fn revenue_goes_to_fee_receiver() {
+    ExpectDeposit::reset();
+
+    struct MockFeeReceiver;
+
+    impl Get<AccountId> for MockFeeReceiver {
+        fn get() -> AccountId {
+            42
+        }
+    }
+
+    type Revenue = ToFeeReceiver<AccountId, AssetId, Balance, Price, MockConvert, ExpectDeposit, MockFeeReceiver>;
+
+    let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+
+    ExpectDeposit::register_expected_fee(42, CORE_ASSET_ID, 1234);
+
+    Revenue::take_revenue((core_id, 1234).into());
+
+    assert_that_fee_is_deposited!();
+}
+
+thread_local! {
+    pub static SWAP_PRICE: RefCell<Price> = RefCell::new(Price::one());
+    pub static SOLD: RefCell<Vec<(AccountId, AssetId, AssetId, Balance, Balance)>> = RefCell::new(Vec::new());
+    pub static SELL_WEIGHT: RefCell<Weight> = RefCell::new(Weight::zero());
+}
+
+/// Storage key written by every sale of `MockSwap`, including failed ones.
+const SELL_ATTEMPT_KEY: &[u8] = b"mock_swap_sell_attempt";
+
+/// Mock router which sells assets for the price set by `MockSwap::set_price`.
+struct MockSwap;
+impl MockSwap {
+    /// Set the amount of `asset_out` received per unit of `asset_in`.
+    fn set_price(price: Price) {
+        SWAP_PRICE.with(|p| *p.borrow_mut() = price);
+    }
+
+    fn set_sell_weight(weight: Weight) {
+        SELL_WEIGHT.with(|w| *w.borrow_mut() = weight);
+    }
+
+    fn sold() -> Vec<(AccountId, AssetId, AssetId, Balance, Balance)> {
+        SOLD.with(|s| s.borrow().clone())
+    }
+
+    /// Reset the global mutable state.
+    fn reset() {
+        SWAP_PRICE.with(|p| *p.borrow_mut() = Price::one());
+        SOLD.with(|s| *s.borrow_mut() = Vec::new());
+        SELL_WEIGHT.with(|w| *w.borrow_mut() = Weight::zero());
+    }
+}
+
+impl RouterSell<AccountId, AssetId, Balance> for MockSwap {
+    fn sell(
+        who: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> DispatchResult {
+        sp_io::storage::set(SELL_ATTEMPT_KEY, &amount_in.encode());
+        let amount_out = SWAP_PRICE.with(|p| p.borrow().saturating_mul_int(amount_in));
+        if amount_out < min_amount_out {
+            return Err(DispatchError::Other("Trading limit reached"));
+        }
+        SOLD.with(|s| {
+            s.borrow_mut()
+                .push((*who, asset_in, asset_out, amount_in, min_amount_out))
+        });
+        Ok(())
+    }
+
+    fn sell_weight() -> Weight {
+        SELL_WEIGHT.with(|w| *w.borrow())
+    }
+}
+
+parameter_types! {
+    const FeeReceiver: AccountId = 42;
+    const NativeAssetId: AssetId = CORE_ASSET_ID;
+    const MaxSlippage: Permill = Permill::from_percent(1);
+}
+
+type SwappingTrader = SwappingMultiCurrencyTrader<
+    AccountId,
+    AssetId,
+    Balance,
+    Price,
+    IdentityFee<Balance>,
+    MockOracle,
+    MockConvert,
+    MockSwap,
+    ExpectDeposit,
+    FeeReceiver,
+    NativeAssetId,
+    MaxSlippage,
+>;
+
+#[test]
+fn swapping_trader_swaps_fees_to_native() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        ExpectDeposit::reset();
+        MockSwap::reset();
+        MockSwap::set_price(Price::saturating_from_integer(2));
+
+        let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+        {
+            let mut trader = SwappingTrader::new();
+
+            let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+            let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), test_payment.into()));
+            assert!(res
+                .expect("buy_weight should succeed because payment == 0.5 * weight")
+                .is_empty());
+
+            ExpectDeposit::register_expected_fee(42, TEST_ASSET_ID, 500_000);
+        }
+
+        assert_that_fee_is_deposited!();
+        // min amount out is the weight fee in native asset minus 1% slippage
+        assert_eq!(
+            MockSwap::sold(),
+            vec![(42, TEST_ASSET_ID, CORE_ASSET_ID, 500_000, 990_000)]
+        );
+    });
+}
+
+#[test]
+fn swapping_trader_does_not_swap_native_asset() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        ExpectDeposit::reset();
+        MockSwap::reset();
+
+        let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+
+        {
+            let mut trader = SwappingTrader::new();
+
+            let core_payment: MultiAsset = (Concrete(core_id), 1_000_000).into();
+            let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), core_payment.into()));
+            assert!(res
+                .expect("buy_weight should succeed because payment == weight")
+                .is_empty());
+
+            ExpectDeposit::register_expected_fee(42, CORE_ASSET_ID, 1_000_000);
+        }
+
+        assert_that_fee_is_deposited!();
+        assert_eq!(MockSwap::sold(), vec![]);
+    });
+}
+
+#[test]
+fn swapping_trader_keeps_fee_in_paid_asset_when_slippage_is_exceeded() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        ExpectDeposit::reset();
+        MockSwap::reset();
+        MockSwap::set_price(Price::from_float(1.9));
+
+        let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+        {
+            let mut trader = SwappingTrader::new();
+
+            let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+            let res = dbg!(trader.buy_weight(Weight::from_ref_time(1_000_000), test_payment.into()));
+            assert!(res
+                .expect("buy_weight should succeed because payment == 0.5 * weight")
+                .is_empty());
+
+            // fee is deposited in the paid asset as with the price-based revenue
+            ExpectDeposit::register_expected_fee(42, TEST_ASSET_ID, 500_000);
+        }
+
+        assert_that_fee_is_deposited!();
+        assert_eq!(MockSwap::sold(), vec![]);
+        // changes of the failed sale are rolled back
+        assert_eq!(sp_io::storage::get(SELL_ATTEMPT_KEY), None);
+    });
+}
+
+#[test]
+fn swapping_trader_does_not_swap_refunded_fees() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        ExpectDeposit::reset();
+        MockSwap::reset();
+
+        let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+        {
+            let mut trader = SwappingTrader::new();
+
+            let weight = Weight::from_ref_time(1_000_000);
+            let test_payment: MultiAsset = (Concrete(test_id), 500_000).into();
+            let res = dbg!(trader.buy_weight(weight, test_payment.clone().into()));
+            assert!(res
+                .expect("buy_weight should succeed because payment == 0.5 * weight")
+                .is_empty());
+            assert_eq!(trader.refund_weight(weight), Some(test_payment));
+        }
+
+        assert_that_fee_is_deposited!();
+        assert_eq!(MockSwap::sold(), vec![]);
+    });
+}
+
+#[test]
+fn swapping_trader_charges_non_refundable_swap_weight_once_per_asset() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        ExpectDeposit::reset();
+        MockSwap::reset();
+        MockSwap::set_price(Price::saturating_from_integer(2));
+        MockSwap::set_sell_weight(Weight::from_ref_time(100_000));
+
+        let core_id = MockConvert::convert(CORE_ASSET_ID).unwrap();
+        let test_id = MockConvert::convert(TEST_ASSET_ID).unwrap();
+
+        {
+            let mut trader = SwappingTrader::new();
+
+            let weight = Weight::from_ref_time(1_000_000);
+            // weight and swap weight at price 0.5
+            let test_payment: MultiAsset = (Concrete(test_id), 550_000).into();
+            assert_ok_empty(trader.buy_weight(weight, test_payment.into()));
+            // swap weight is charged only once per asset
+            assert_ok_empty(trader.buy_weight(weight, (Concrete(test_id), 500_000).into()));
+            // native asset is not swapped
+            assert_ok_empty(trader.buy_weight(weight, (Concrete(core_id), 1_000_000).into()));
+
+            assert_eq!(
+                trader.refund_weight(weight),
+                Some((Concrete(core_id), 1_000_000).into())
+            );
+            // all bought weight is refunded, but not the swap weight
+            assert_eq!(
+                trader.refund_weight(weight.saturating_mul(2)),
+                Some((Concrete(test_id), 1_000_000).into())
+            );
+
+            ExpectDeposit::register_expected_fee(42, TEST_ASSET_ID, 50_000);
+        }
+
+        assert_that_fee_is_deposited!();
+        assert_eq!(
+            MockSwap::sold(),
+            vec![(42, TEST_ASSET_ID, CORE_ASSET_ID, 50_000, 99_000)]
+        );
+    });
+}
+
+#[macro_export]
+macro_rules! assert_that_fee_is_deposited {
+    () => {
+        EXPECTED_DEPOSITS.with(|remaining| {
+            assert!(
+                remaining.borrow().is_empty(),
+                "There should be no expected fees remaining. Remaining: {:?}",
+                remaining
+            );
+        });
+    };
+}
+
+mod refund_invariants {
+    use super::*;
+    use proptest::prelude::*;
+    use sp_std::collections::btree_map::BTreeMap;
+
+    type Trader = MultiCurrencyTrader<AssetId, Balance, Price, IdentityFee<Balance>, MockOracle, MockConvert, ()>;
+
+    fn asset() -> impl Strategy<Value = AssetId> {
+        prop_oneof![Just(CORE_ASSET_ID), Just(TEST_ASSET_ID), Just(CHEAP_ASSET_ID)]
+    }
+
+    fn weight() -> impl Strategy<Value = u64> {
+        1_000..1_000_000_000_000u64
+    }
+
+    /// Value of `amount` of `asset` in native asset at the price recorded by the trader.
+    fn native_value(asset: AssetId, amount: Balance) -> Balance {
+        MockOracle::price(asset)
+            .and_then(|price| price.reciprocal())
+            .unwrap()
+            .saturating_mul_int(amount)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1_000))]
+        #[test]
+        fn refunded_value_should_not_exceed_paid_value(
+            purchases in prop::collection::vec((asset(), weight()), 1..10),
+            refunds in prop::collection::vec(weight(), 1..20),
+        ) {
+            let mut trader = Trader::new();
+            let mut paid: BTreeMap<AssetId, Balance> = BTreeMap::new();
+            let mut refunded: BTreeMap<AssetId, Balance> = BTreeMap::new();
+
+            for (asset, weight) in purchases {
+                let location = MockConvert::convert(asset).unwrap();
+                let amount = MockOracle::price(asset).unwrap().saturating_mul_int(weight as Balance);
+                let res = trader.buy_weight(Weight::from_ref_time(weight), (Concrete(location), amount).into());
+                prop_assert!(res.unwrap().is_empty());
+                *paid.entry(asset).or_default() += amount;
+            }
+
+            for weight in refunds {
+                if let Some(MultiAsset { id: Concrete(location), fun: Fungible(amount) }) =
+                    trader.refund_weight(Weight::from_ref_time(weight))
+                {
+                    let asset = MockConvert::convert(location).unwrap();
+                    // a single refund is never worth more than the refunded weight
+                    prop_assert!(native_value(asset, amount) <= weight as Balance);
+                    *refunded.entry(asset).or_default() += amount;
+                }
+            }
+
+            for (asset, amount) in refunded.iter() {
+                prop_assert!(*amount <= paid[asset]);
+            }
+            let total_paid: Balance = paid.iter().map(|(asset, amount)| native_value(*asset, *amount)).sum();
+            let total_refunded: Balance = refunded.iter().map(|(asset, amount)| native_value(*asset, *amount)).sum();
+            prop_assert!(total_refunded <= total_paid);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1_000))]
+        #[test]
+        fn refunding_all_bought_weight_should_return_all_paid_assets(
+            purchases in prop::collection::vec((asset(), weight()), 1..10),
+        ) {
+            let mut trader = Trader::new();
+            let mut paid: BTreeMap<AssetId, Balance> = BTreeMap::new();
+            let mut total_weight = 0u64;
+
+            for (asset, weight) in purchases.iter() {
+                let location = MockConvert::convert(*asset).unwrap();
+                let amount = MockOracle::price(*asset).unwrap().saturating_mul_int(*weight as Balance);
+                let res = trader.buy_weight(Weight::from_ref_time(*weight), (Concrete(location), amount).into());
+                prop_assert!(res.unwrap().is_empty());
+                *paid.entry(*asset).or_default() += amount;
+                total_weight += weight;
+            }
+
+            // every call refunds one asset, the rest of the weight is refunded by the next call
+            let mut refunded: BTreeMap<AssetId, Balance> = BTreeMap::new();
+            for _ in 0..purchases.len() {
+                if let Some(MultiAsset { id: Concrete(location), fun: Fungible(amount) }) =
+                    trader.refund_weight(Weight::from_ref_time(total_weight))
+                {
+                    *refunded.entry(MockConvert::convert(location).unwrap()).or_default() += amount;
+                }
+            }
+
+            prop_assert_eq!(refunded, paid);
+            prop_assert_eq!(trader.refund_weight(Weight::from_ref_time(total_weight)), None);
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1_000))]
+        #[test]
+        fn refunds_should_be_last_in_first_out(
+            purchases in prop::collection::vec((asset(), weight()), 1..10),
+        ) {
+            let mut trader = Trader::new();
+            for (asset, weight) in purchases.iter() {
+                let location = MockConvert::convert(*asset).unwrap();
+                let amount = MockOracle::price(*asset).unwrap().saturating_mul_int(*weight as Balance);
+                let res = trader.buy_weight(Weight::from_ref_time(*weight), (Concrete(location), amount).into());
+                prop_assert!(res.unwrap().is_empty());
+            }
+
+            let last_asset = purchases.last().unwrap().0;
+            match trader.refund_weight(Weight::from_ref_time(1)) {
+                Some(MultiAsset { id: Concrete(location), .. }) => {
+                    prop_assert_eq!(MockConvert::convert(location), Some(last_asset));
+                }
+                refund => prop_assert!(false, "unexpected refund {:?}", refund),
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// This file is part of HydraDX.
+
+// Copyright (C) 2020-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{assert_ok, BoundedVec};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// Non-native currency with a price, so that dusting it reads the price oracle.
+const DUST_CURRENCY: u32 = 1;
+
+/// Set fresh reward and dust accounts and fund the reward account.
+fn init_accounts<T: Config>() {
+    let reward_account: T::AccountId = account("reward", 0, SEED);
+    let dust_account: T::AccountId = account("dust", 0, SEED);
+
+    assert_ok!(T::MultiCurrency::deposit(
+        T::NativeCurrencyId::get(),
+        &reward_account,
+        1_000_000_000_000_000u128.saturated_into()
+    ));
+
+    RewardAccount::<T>::put(reward_account);
+    DustAccount::<T>::put(dust_account);
+}
+
+/// Create an account holding dust of `DUST_CURRENCY`.
+fn dusty_account<T: Config>(index: u32) -> (T::AccountId, T::CurrencyId)
+where
+    T::CurrencyId: From<u32>,
+{
+    let who: T::AccountId = account("dusty", index, SEED);
+    let currency_id: T::CurrencyId = DUST_CURRENCY.into();
+    let dust = T::MinCurrencyDeposits::get(&currency_id).saturating_sub(1u32.into());
+
+    assert_ok!(T::MultiCurrency::deposit(currency_id, &who, dust));
+
+    (who, currency_id)
+}
+
+benchmarks! {
+    where_clause {
+        where T::CurrencyId: From<u32>,
+    }
+
+    dust_account {
+        init_accounts::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        let (who, currency_id) = dusty_account::<T>(0);
+    }: _(RawOrigin::Signed(caller), who.clone(), currency_id)
+    verify {
+        assert!(T::MultiCurrency::total_balance(currency_id, &who).is_zero());
+    }
+
+    dust_accounts {
+        let n in 1 .. T::MaxDustAccounts::get();
+
+        init_accounts::<T>();
+        let caller: T::AccountId = whitelisted_caller();
+        let accounts: BoundedVec<_, T::MaxDustAccounts> = (0..n)
+            .map(dusty_account::<T>)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+    }: _(RawOrigin::Signed(caller), accounts.clone())
+    verify {
+        for (who, currency_id) in accounts {
+            assert!(T::MultiCurrency::total_balance(currency_id, &who).is_zero());
+        }
+    }
+
+    queue_dust_candidate {
+        let (who, currency_id) = dusty_account::<T>(0);
+    }: { Pallet::<T>::queue_dust_candidate(&who, currency_id) }
+    verify {
+        assert!(DustQueue::<T>::contains_key((who, currency_id)));
+    }
+
+    process_dust_queue {
+        let n in 1 .. T::MaxDustQueueLength::get();
+
+        init_accounts::<T>();
+        for i in 0..n {
+            let (who, currency_id) = dusty_account::<T>(i);
+            Pallet::<T>::queue_dust_candidate(&who, currency_id);
+        }
+        assert_eq!(DustQueueLength::<T>::get(), n);
+    }: { Pallet::<T>::process_dust_queue(Weight::MAX) }
+    verify {
+        assert_eq!(DustQueueLength::<T>::get(), 0);
+    }
+
+    set_reward_policy {
+        let origin = T::BlacklistUpdateOrigin::try_successful_origin().unwrap();
+        let currency_id: T::CurrencyId = DUST_CURRENCY.into();
+        let policy = RewardPolicy {
+            reward: T::Reward::get(),
+            max_dust_value_share: Permill::from_percent(50),
+        };
+    }: {
+        assert_ok!(Pallet::<T>::set_reward_policy(origin, currency_id, Some(policy)));
+    }
+    verify {
+        assert_eq!(RewardPolicies::<T>::get(currency_id), Some(policy));
+    }
+
+    add_nondustable_entry {
+        let origin = T::BlacklistUpdateOrigin::try_successful_origin().unwrap();
+        let (who, currency_id) = dusty_account::<T>(0);
+        let reason = vec![0u8; T::MaxReasonLength::get() as usize];
+        let now = frame_system::Pallet::<T>::block_number();
+        let expires_at = now.saturating_add(20u32.into());
+
+        // the existing entry with an expiry is replaced
+        let existing_origin = T::BlacklistUpdateOrigin::try_successful_origin().unwrap();
+        assert_ok!(Pallet::<T>::add_nondustable_entry(
+            existing_origin,
+            who.clone(),
+            currency_id,
+            Some(now.saturating_add(10u32.into())),
+            reason.clone()
+        ));
+    }: {
+        assert_ok!(Pallet::<T>::add_nondustable_entry(origin, who.clone(), currency_id, Some(expires_at), reason));
+    }
+    verify {
+        assert_eq!(NonDustableEntries::<T>::get(&who, currency_id).unwrap().expires_at, Some(expires_at));
+    }
+
+    remove_nondustable_entry {
+        let origin = T::BlacklistUpdateOrigin::try_successful_origin().unwrap();
+        let (who, currency_id) = dusty_account::<T>(0);
+        let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(10u32.into());
+
+        let existing_origin = T::BlacklistUpdateOrigin::try_successful_origin().unwrap();
+        assert_ok!(Pallet::<T>::add_nondustable_entry(
+            existing_origin,
+            who.clone(),
+            currency_id,
+            Some(expires_at),
+            vec![0u8; T::MaxReasonLength::get() as usize]
+        ));
+    }: {
+        assert_ok!(Pallet::<T>::remove_nondustable_entry(origin, who.clone(), currency_id));
+    }
+    verify {
+        assert!(NonDustableEntries::<T>::get(&who, currency_id).is_none());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{ExtBuilder, Test};
+    use frame_benchmarking::impl_benchmark_test_suite;
+
+    impl_benchmark_test_suite!(Pallet, ExtBuilder::default().build(), Test);
+}
@@ -24,10 +24,18 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod benchmarking;
 pub mod migration;
 pub mod weights;
 
-use frame_support::{dispatch::DispatchResult, ensure, traits::Contains, traits::Get};
+use frame_support::{
+    dispatch::{DispatchResult, DispatchResultWithPostInfo},
+    ensure,
+    storage::with_storage_layer,
+    traits::Contains,
+    traits::Get,
+    weights::Weight,
+};
 
 use orml_traits::{
     arithmetic::{Signed, SimpleArithmetic},
@@ -36,11 +44,46 @@ use orml_traits::{
 
 use frame_system::ensure_signed;
 
+use codec::{Decode, Encode};
+use frame_support::sp_runtime::{
+    traits::{Saturating, Zero},
+    DispatchError, FixedPointNumber, FixedU128, Permill, RuntimeDebug, SaturatedConversion,
+};
+use hydradx_traits::NativePriceOracle;
+use scale_info::TypeInfo;
 use sp_std::convert::{TryFrom, TryInto};
+use sp_std::vec::Vec;
+use weights::WeightInfo;
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
+/// Reward paid in native currency for dusting an account of a currency.
+///
+/// The reward is capped by `max_dust_value_share` of the dust value in native currency, so dusting is only rewarded
+/// with a fraction of what is recovered.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RewardPolicy<Balance> {
+    pub reward: Balance,
+    pub max_dust_value_share: Permill,
+}
+
+/// Exclusion of an account from dusting of a single currency.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct NonDustableEntry<BlockNumber, Reason> {
+    /// Block at which the entry expires. Permanent if `None`.
+    pub expires_at: Option<BlockNumber>,
+    /// Short tag describing why the account is excluded.
+    pub reason: Reason,
+}
+
+impl<BlockNumber: PartialOrd, Reason> NonDustableEntry<BlockNumber, Reason> {
+    /// Returns true if the entry is still in effect at block `now`.
+    pub fn is_active(&self, now: &BlockNumber) -> bool {
+        self.expires_at.as_ref().map_or(true, |expires_at| expires_at > now)
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -63,6 +106,39 @@ pub mod pallet {
     /// Accounts excluded from dusting.
     pub type AccountBlacklist<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
 
+    pub type ReasonOf<T> = BoundedVec<u8, <T as Config>::MaxReasonLength>;
+    pub type NonDustableEntryOf<T> = NonDustableEntry<BlockNumberFor<T>, ReasonOf<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn nondustable_entry)]
+    /// Accounts excluded from dusting of a single currency.
+    pub type NonDustableEntries<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::CurrencyId,
+        NonDustableEntryOf<T>,
+        OptionQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn nondustable_expiries)]
+    /// Non-dustable entries which expire at given block. Pruned in `on_initialize`.
+    pub type NonDustableExpiries<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<(T::AccountId, T::CurrencyId), T::MaxExpiriesPerBlock>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn reward_policy)]
+    /// Reward policies of currencies which differ from the default one.
+    pub type RewardPolicies<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::CurrencyId, RewardPolicy<T::Balance>, OptionQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn reward_account)]
     /// Account to take reward from.
@@ -73,8 +149,25 @@ pub mod pallet {
     /// Account to send dust to.
     pub type DustAccount<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    #[pallet::storage]
+    /// Accounts which might be dustable, keyed by account and currency. Drained in `on_idle`.
+    pub type DustQueue<T: Config> = StorageMap<_, Blake2_128Concat, (T::AccountId, T::CurrencyId), (), OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn dust_queue_length)]
+    /// Number of entries in `DustQueue`.
+    pub type DustQueueLength<T: Config> = StorageValue<_, u32, ValueQuery>;
+
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Self::prune_expired_entries(n)
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::process_dust_queue(remaining_weight)
+        }
+    }
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -114,17 +207,36 @@ pub mod pallet {
         /// The minimum amount required to keep an account.
         type MinCurrencyDeposits: GetByKey<Self::CurrencyId, Self::Balance>;
 
-        /// Reward amount
+        /// Reward amount for currencies without a reward policy
         #[pallet::constant]
         type Reward: Get<Self::Balance>;
 
+        /// Price oracle used to determine the value of dust in native currency
+        type NativePriceOracle: NativePriceOracle<Self::CurrencyId, FixedU128>;
+
         /// Native Asset Id
         #[pallet::constant]
         type NativeCurrencyId: Get<Self::CurrencyId>;
 
-        /// The origin which can manage whiltelist.
+        /// The origin which can manage whiltelist and reward policies.
         type BlacklistUpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
+        /// Maximum number of accounts which can be dusted in one `dust_accounts` call.
+        #[pallet::constant]
+        type MaxDustAccounts: Get<u32>;
+
+        /// Maximum number of entries in the dust queue. Candidates are not queued if the queue is full.
+        #[pallet::constant]
+        type MaxDustQueueLength: Get<u32>;
+
+        /// Maximum length of the reason tag of a non-dustable entry.
+        #[pallet::constant]
+        type MaxReasonLength: Get<u32>;
+
+        /// Maximum number of non-dustable entries expiring at the same block.
+        #[pallet::constant]
+        type MaxExpiriesPerBlock: Get<u32>;
+
         /// Weight information for extrinsics in this module.
         type WeightInfo: WeightInfo;
     }
@@ -196,6 +308,21 @@ pub mod pallet {
 
         /// Reserve account is not set.
         ReserveAccountNotSet,
+
+        /// Number of accounts to dust exceeds `MaxDustAccounts`.
+        TooManyAccounts,
+
+        /// Reason tag is longer than `MaxReasonLength`.
+        ReasonTooLong,
+
+        /// Expiry block is not in the future.
+        InvalidExpiry,
+
+        /// Too many non-dustable entries expire at the given block.
+        TooManyExpiries,
+
+        /// Account has no non-dustable entry for the currency.
+        NonDustableEntryNotFound,
     }
 
     #[pallet::event]
@@ -209,6 +336,39 @@ pub mod pallet {
 
         /// Account removed from non-dustable list.
         Removed { who: T::AccountId },
+
+        /// Account excluded from dusting of a currency.
+        NonDustableEntryAdded {
+            who: T::AccountId,
+            currency_id: T::CurrencyId,
+            expires_at: Option<BlockNumberFor<T>>,
+            reason: ReasonOf<T>,
+        },
+
+        /// Non-dustable entry removed.
+        NonDustableEntryRemoved {
+            who: T::AccountId,
+            currency_id: T::CurrencyId,
+        },
+
+        /// Non-dustable entry expired and was pruned.
+        NonDustableEntryExpired {
+            who: T::AccountId,
+            currency_id: T::CurrencyId,
+        },
+
+        /// Reward policy of a currency set or removed.
+        RewardPolicySet {
+            currency_id: T::CurrencyId,
+            policy: Option<RewardPolicy<T::Balance>>,
+        },
+
+        /// Multiple accounts dusted in one batch.
+        BatchDusted {
+            who: T::AccountId,
+            dusted: u32,
+            skipped: u32,
+        },
     }
 
     #[pallet::call]
@@ -223,18 +383,7 @@ pub mod pallet {
         pub fn dust_account(origin: OriginFor<T>, account: T::AccountId, currency_id: T::CurrencyId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(Self::blacklisted(&account).is_none(), Error::<T>::AccountBlacklisted);
-
-            let (dustable, dust) = Self::is_dustable(&account, currency_id);
-
-            ensure!(dust != T::Balance::from(0u32), Error::<T>::ZeroBalance);
-
-            ensure!(dustable, Error::<T>::BalanceSufficient);
-
-            // Error should never occur here
-            let dust_dest_account = Self::dust_dest_account().ok_or(Error::<T>::DustAccountNotSet)?;
-
-            Self::transfer_dust(&account, &dust_dest_account, currency_id, dust)?;
+            let dust = Self::do_dust(&account, currency_id)?;
 
             Self::deposit_event(Event::Dusted {
                 who: account,
@@ -247,6 +396,59 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Dust multiple accounts in one call.
+        ///
+        /// Entries which cannot be dusted (blacklisted, zero or sufficient balance) are skipped instead of failing
+        /// the whole call. Caller is rewarded for every dusted entry.
+        ///
+        /// Skipped entries are only charged for the reads done to find out they cannot be dusted, the rest of
+        /// their weight is refunded.
+        ///
+        /// Emits one `BatchDusted` event with the number of dusted and skipped entries.
+        #[pallet::call_index(3)]
+        #[pallet::weight(<T as Config>::WeightInfo::dust_accounts(accounts.len() as u32))]
+        pub fn dust_accounts(
+            origin: OriginFor<T>,
+            accounts: BoundedVec<(T::AccountId, T::CurrencyId), T::MaxDustAccounts>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+
+            let mut dusted: u32 = 0;
+            let mut skipped: u32 = 0;
+            for (account, currency_id) in accounts {
+                match with_storage_layer(|| Self::do_dust(&account, currency_id)) {
+                    Ok(dust) => {
+                        dusted.saturating_inc();
+                        // Ignore the result, it fails - no problem.
+                        let _ = Self::reward_duster(&who, currency_id, dust);
+                    }
+                    Err(_) => skipped.saturating_inc(),
+                }
+            }
+
+            Self::deposit_event(Event::BatchDusted { who, dusted, skipped });
+
+            Ok(Some(Self::dust_accounts_weight(dusted, skipped)).into())
+        }
+
+        /// Set reward policy of a currency. Removing the policy falls back to the default policy, which pays
+        /// `T::Reward` capped by the whole dust value.
+        #[pallet::call_index(4)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_reward_policy())]
+        pub fn set_reward_policy(
+            origin: OriginFor<T>,
+            currency_id: T::CurrencyId,
+            policy: Option<RewardPolicy<T::Balance>>,
+        ) -> DispatchResult {
+            T::BlacklistUpdateOrigin::ensure_origin(origin)?;
+
+            RewardPolicies::<T>::set(currency_id, policy);
+
+            Self::deposit_event(Event::RewardPolicySet { currency_id, policy });
+
+            Ok(())
+        }
+
         /// Add account to list of non-dustable account. Account whihc are excluded from udsting.
         /// If such account should be dusted - `AccountBlacklisted` error is returned.
         /// Only root can perform this action.
@@ -280,9 +482,223 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Exclude account from dusting of given currency.
+        ///
+        /// Entry is permanent if `expires_at` is `None`, otherwise it is pruned at `expires_at` block.
+        /// An existing entry of the same account and currency is replaced.
+        #[pallet::call_index(5)]
+        #[pallet::weight(<T as Config>::WeightInfo::add_nondustable_entry())]
+        pub fn add_nondustable_entry(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            currency_id: T::CurrencyId,
+            expires_at: Option<BlockNumberFor<T>>,
+            reason: Vec<u8>,
+        ) -> DispatchResult {
+            T::BlacklistUpdateOrigin::ensure_origin(origin)?;
+
+            let reason: ReasonOf<T> = reason.try_into().map_err(|_| Error::<T>::ReasonTooLong)?;
+
+            if let Some(expires_at) = expires_at {
+                ensure!(
+                    expires_at > frame_system::Pallet::<T>::block_number(),
+                    Error::<T>::InvalidExpiry
+                );
+            }
+
+            Self::remove_entry(&account, currency_id);
+
+            if let Some(expires_at) = expires_at {
+                NonDustableExpiries::<T>::try_mutate(expires_at, |expiries| {
+                    expiries.try_push((account.clone(), currency_id))
+                })
+                .map_err(|_| Error::<T>::TooManyExpiries)?;
+            }
+
+            NonDustableEntries::<T>::insert(
+                &account,
+                currency_id,
+                NonDustableEntry {
+                    expires_at,
+                    reason: reason.clone(),
+                },
+            );
+
+            Self::deposit_event(Event::NonDustableEntryAdded {
+                who: account,
+                currency_id,
+                expires_at,
+                reason,
+            });
+
+            Ok(())
+        }
+
+        /// Remove non-dustable entry of account and currency.
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_nondustable_entry())]
+        pub fn remove_nondustable_entry(
+            origin: OriginFor<T>,
+            account: T::AccountId,
+            currency_id: T::CurrencyId,
+        ) -> DispatchResult {
+            T::BlacklistUpdateOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                Self::remove_entry(&account, currency_id),
+                Error::<T>::NonDustableEntryNotFound
+            );
+
+            Self::deposit_event(Event::NonDustableEntryRemoved {
+                who: account,
+                currency_id,
+            });
+
+            Ok(())
+        }
     }
 }
 impl<T: Config> Pallet<T> {
+    /// Transfer the whole balance of `currency_id` of `account` to the dust account if the account can be dusted.
+    ///
+    /// Returns the dusted amount.
+    fn do_dust(account: &T::AccountId, currency_id: T::CurrencyId) -> Result<T::Balance, DispatchError> {
+        ensure!(
+            !Self::is_nondustable(account, currency_id),
+            Error::<T>::AccountBlacklisted
+        );
+
+        let (dustable, dust) = Self::is_dustable(account, currency_id);
+
+        ensure!(dust != T::Balance::from(0u32), Error::<T>::ZeroBalance);
+
+        ensure!(dustable, Error::<T>::BalanceSufficient);
+
+        // Error should never occur here
+        let dust_dest_account = Self::dust_dest_account().ok_or(Error::<T>::DustAccountNotSet)?;
+
+        Self::transfer_dust(account, &dust_dest_account, currency_id, dust)?;
+
+        Ok(dust)
+    }
+
+    /// Actual weight of `dust_accounts` which dusted `dusted` entries and skipped `skipped` entries.
+    ///
+    /// A skipped entry reads the blacklist, the non-dustable entry, the balance and the dust account at most.
+    fn dust_accounts_weight(dusted: u32, skipped: u32) -> Weight {
+        T::WeightInfo::dust_accounts(dusted).saturating_add(T::DbWeight::get().reads(4).saturating_mul(skipped as u64))
+    }
+
+    /// Add `account` to the dust queue if its balance of `currency_id` is dustable.
+    fn queue_dust_candidate(account: &T::AccountId, currency_id: T::CurrencyId) {
+        if Self::is_nondustable(account, currency_id) {
+            return;
+        }
+
+        let (dustable, dust) = Self::is_dustable(account, currency_id);
+        if !dustable || dust == T::Balance::from(0u32) {
+            return;
+        }
+
+        let key = (account.clone(), currency_id);
+        let queue_length = Self::dust_queue_length();
+        if DustQueue::<T>::contains_key(&key) || queue_length >= T::MaxDustQueueLength::get() {
+            return;
+        }
+
+        DustQueue::<T>::insert(key, ());
+        DustQueueLength::<T>::put(queue_length.saturating_add(1));
+    }
+
+    /// Dust queued accounts as long as `remaining_weight` allows.
+    ///
+    /// Entries are removed from the queue whether or not they could be dusted, e.g. because the balance increased in
+    /// the meantime. Returns the consumed weight.
+    fn process_dust_queue(remaining_weight: Weight) -> Weight {
+        // reading the queue length and writing it back
+        let base_weight = T::WeightInfo::process_dust_queue(0);
+        // iterating over, removing and dusting a queue entry
+        let entry_weight = T::WeightInfo::process_dust_queue(1).saturating_sub(base_weight);
+
+        // Entries are never processed for free, otherwise the number of entries would not be bounded by the weight.
+        if entry_weight.ref_time().is_zero() || remaining_weight.any_lt(base_weight.saturating_add(entry_weight)) {
+            return Weight::zero();
+        }
+
+        let queue_length = Self::dust_queue_length();
+        if queue_length == 0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let max_entries = (remaining_weight.saturating_sub(base_weight).ref_time() / entry_weight.ref_time())
+            .min(queue_length as u64);
+
+        let entries: Vec<(T::AccountId, T::CurrencyId)> =
+            DustQueue::<T>::iter_keys().take(max_entries as usize).collect();
+
+        for (account, currency_id) in entries.iter() {
+            DustQueue::<T>::remove((account, currency_id));
+            if let Ok(dust) = with_storage_layer(|| Self::do_dust(account, *currency_id)) {
+                Self::deposit_event(Event::Dusted {
+                    who: account.clone(),
+                    amount: dust,
+                });
+            }
+        }
+
+        DustQueueLength::<T>::put(queue_length.saturating_sub(entries.len() as u32));
+
+        T::WeightInfo::process_dust_queue(entries.len() as u32)
+    }
+
+    /// Returns true if account is excluded from dusting of all currencies or has an active entry for `currency_id`.
+    fn is_nondustable(account: &T::AccountId, currency_id: T::CurrencyId) -> bool {
+        if Self::blacklisted(account).is_some() {
+            return true;
+        }
+
+        let now = frame_system::Pallet::<T>::block_number();
+        Self::nondustable_entry(account, currency_id).map_or(false, |entry| entry.is_active(&now))
+    }
+
+    /// Remove non-dustable entry together with its expiry record. Returns false if there was no entry.
+    fn remove_entry(account: &T::AccountId, currency_id: T::CurrencyId) -> bool {
+        let entry = match NonDustableEntries::<T>::take(account, currency_id) {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        if let Some(expires_at) = entry.expires_at {
+            NonDustableExpiries::<T>::mutate_exists(expires_at, |maybe_expiries| {
+                if let Some(expiries) = maybe_expiries {
+                    expiries.retain(|(who, id)| !(who == account && *id == currency_id));
+                    if expiries.is_empty() {
+                        *maybe_expiries = None;
+                    }
+                }
+            });
+        }
+
+        true
+    }
+
+    /// Remove non-dustable entries expiring at block `n`. Returns the consumed weight.
+    fn prune_expired_entries(n: BlockNumberFor<T>) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let expiries = NonDustableExpiries::<T>::take(n);
+
+        for (account, currency_id) in expiries.iter() {
+            NonDustableEntries::<T>::remove(account, currency_id);
+            Self::deposit_event(Event::NonDustableEntryExpired {
+                who: account.clone(),
+                currency_id: *currency_id,
+            });
+        }
+
+        db_weight.reads_writes(1, 1u64.saturating_add(expiries.len() as u64))
+    }
+
     /// Check is account's balance is below minimum deposit.
     fn is_dustable(account: &T::AccountId, currency_id: T::CurrencyId) -> (bool, T::Balance) {
         let ed = T::MinCurrencyDeposits::get(&currency_id);
@@ -293,16 +709,46 @@ impl<T: Config> Pallet<T> {
     }
 
     /// Send reward to account which did the dusting.
-    fn reward_duster(_duster: &T::AccountId, _currency_id: T::CurrencyId, _dust: T::Balance) -> DispatchResult {
+    fn reward_duster(duster: &T::AccountId, currency_id: T::CurrencyId, dust: T::Balance) -> DispatchResult {
         // Error should never occur here
         let reserve_account = Self::reward_account().ok_or(Error::<T>::ReserveAccountNotSet)?;
-        let reward = T::Reward::get();
+        let reward = Self::calculate_reward(currency_id, dust);
+
+        if reward.is_zero() {
+            return Ok(());
+        }
 
-        T::MultiCurrency::transfer(T::NativeCurrencyId::get(), &reserve_account, _duster, reward)?;
+        T::MultiCurrency::transfer(T::NativeCurrencyId::get(), &reserve_account, duster, reward)?;
 
         Ok(())
     }
 
+    /// Calculate reward for dusting `dust` of `currency_id` according to the reward policy of the currency.
+    ///
+    /// Dust of currencies without a price is considered worthless and not rewarded.
+    fn calculate_reward(currency_id: T::CurrencyId, dust: T::Balance) -> T::Balance {
+        let policy = Self::reward_policy(currency_id).unwrap_or(RewardPolicy {
+            reward: T::Reward::get(),
+            max_dust_value_share: Permill::one(),
+        });
+
+        let dust: u128 = dust.saturated_into();
+        let dust_value = if currency_id == T::NativeCurrencyId::get() {
+            Some(dust)
+        } else {
+            T::NativePriceOracle::price(currency_id)
+                .and_then(|price| price.reciprocal())
+                .map(|price| price.saturating_mul_int(dust))
+        };
+
+        let max_reward: T::Balance = dust_value
+            .map(|value| policy.max_dust_value_share.mul_floor(value))
+            .unwrap_or_default()
+            .saturated_into();
+
+        policy.reward.min(max_reward)
+    }
+
     /// Transfer dust amount to selected DustAccount ( usually treasury)
     fn transfer_dust(
         from: &T::AccountId,
@@ -314,7 +760,7 @@ impl<T: Config> Pallet<T> {
     }
 }
 
-use orml_traits::currency::OnDust;
+use orml_traits::currency::{OnDust, OnTransfer};
 
 use sp_std::marker::PhantomData;
 pub struct DusterWhitelist<T>(PhantomData<T>);
@@ -327,14 +773,92 @@ impl<T: Config> OnDust<T::AccountId, T::CurrencyId, T::Balance> for Pallet<T> {
     }
 }
 
+/// Queues the sender of a transfer for dusting if the remaining balance is below the minimum deposit.
+///
+/// Meant to be used as `PostTransfer` currency hook. Never fails so transfers are not affected.
+///
+/// The weight of the hook has to be included in the weight of the transfers, e.g. by wrapping the weights of
+/// `orml_tokens` and `pallet_currencies` with `TokensWeightWithDustQueue` and `CurrenciesWeightWithDustQueue`.
+impl<T: Config> OnTransfer<T::AccountId, T::CurrencyId, T::Balance> for Pallet<T> {
+    fn on_transfer(
+        currency_id: T::CurrencyId,
+        from: &T::AccountId,
+        _to: &T::AccountId,
+        _amount: T::Balance,
+    ) -> DispatchResult {
+        Self::queue_dust_candidate(from, currency_id);
+        Ok(())
+    }
+}
+
+/// Weights of `orml_tokens` with the weight of queueing the sender for dusting added to the transfers.
+pub struct TokensWeightWithDustQueue<T, W>(PhantomData<(T, W)>);
+
+impl<T: Config, W: orml_tokens::WeightInfo> orml_tokens::WeightInfo for TokensWeightWithDustQueue<T, W> {
+    fn transfer() -> Weight {
+        W::transfer().saturating_add(T::WeightInfo::queue_dust_candidate())
+    }
+
+    fn transfer_all() -> Weight {
+        W::transfer_all().saturating_add(T::WeightInfo::queue_dust_candidate())
+    }
+
+    fn transfer_keep_alive() -> Weight {
+        W::transfer_keep_alive().saturating_add(T::WeightInfo::queue_dust_candidate())
+    }
+
+    fn force_transfer() -> Weight {
+        W::force_transfer().saturating_add(T::WeightInfo::queue_dust_candidate())
+    }
+
+    fn set_balance() -> Weight {
+        W::set_balance()
+    }
+}
+
+/// Weights of `pallet_currencies` with the weight of queueing the sender for dusting added to the transfer of
+/// non-native currencies. Native currency transfers do not call the transfer hook.
+pub struct CurrenciesWeightWithDustQueue<T, W>(PhantomData<(T, W)>);
+
+impl<T: Config, W: pallet_currencies::WeightInfo> pallet_currencies::WeightInfo
+    for CurrenciesWeightWithDustQueue<T, W>
+{
+    fn transfer_non_native_currency() -> Weight {
+        W::transfer_non_native_currency().saturating_add(T::WeightInfo::queue_dust_candidate())
+    }
+
+    fn transfer_native_currency() -> Weight {
+        W::transfer_native_currency()
+    }
+
+    fn update_balance_non_native_currency() -> Weight {
+        W::update_balance_non_native_currency()
+    }
+
+    fn update_balance_native_currency_creating() -> Weight {
+        W::update_balance_native_currency_creating()
+    }
+
+    fn update_balance_native_currency_killing() -> Weight {
+        W::update_balance_native_currency_killing()
+    }
+}
+
+/// Accounts excluded from dusting of all currencies.
 impl<T: Config> Contains<T::AccountId> for DusterWhitelist<T> {
     fn contains(t: &T::AccountId) -> bool {
         AccountBlacklist::<T>::contains_key(t)
     }
 }
 
-use frame_support::sp_runtime::DispatchError;
-use hydradx_traits::pools::DustRemovalAccountWhitelist;
+/// Accounts excluded from dusting of a currency, either of all currencies or by an active non-dustable entry.
+impl<T: Config> Contains<(T::AccountId, T::CurrencyId)> for DusterWhitelist<T> {
+    fn contains(t: &(T::AccountId, T::CurrencyId)) -> bool {
+        Pallet::<T>::is_nondustable(&t.0, t.1)
+    }
+}
+
+use hydradx_traits::pools::{DustRemovalAccountWhitelist, DustRemovalCurrencyWhitelist};
 
 impl<T: Config> DustRemovalAccountWhitelist<T::AccountId> for Pallet<T> {
     type Error = DispatchError;
@@ -354,3 +878,29 @@ impl<T: Config> DustRemovalAccountWhitelist<T::AccountId> for Pallet<T> {
         })
     }
 }
+
+/// Adds and removes permanent non-dustable entries without a reason tag.
+impl<T: Config> DustRemovalCurrencyWhitelist<T::AccountId, T::CurrencyId> for Pallet<T> {
+    type Error = DispatchError;
+
+    fn add_account_currency(account: &T::AccountId, currency_id: T::CurrencyId) -> Result<(), Self::Error> {
+        Self::remove_entry(account, currency_id);
+        NonDustableEntries::<T>::insert(
+            account,
+            currency_id,
+            NonDustableEntry {
+                expires_at: None,
+                reason: ReasonOf::<T>::default(),
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_account_currency(account: &T::AccountId, currency_id: T::CurrencyId) -> Result<(), Self::Error> {
+        ensure!(
+            Self::remove_entry(account, currency_id),
+            Error::<T>::NonDustableEntryNotFound
+        );
+        Ok(())
+    }
+}
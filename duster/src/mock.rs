@@ -3,17 +3,21 @@ use crate as duster;
 use frame_support::parameter_types;
 use frame_support::traits::{Everything, GenesisBuild, Nothing, OnKilledAccount};
 
+use orml_traits::currency::MutationHooks;
 use orml_traits::parameter_type_with_key;
 use pallet_currencies::BasicCurrencyAdapter;
 
 use crate::Config;
+use crate::{CurrenciesWeightWithDustQueue, TokensWeightWithDustQueue};
 use frame_system as system;
 
 use sp_core::H256;
 
+use hydradx_traits::NativePriceOracle;
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
+    traits::{BlakeTwo256, IdentityLookup, One},
+    FixedU128,
 };
 
 use frame_support::weights::Weight;
@@ -66,6 +70,10 @@ parameter_types! {
 
     pub NativeCurrencyId: AssetId = 0;
     pub Reward: Balance = 10_000;
+    pub const MaxDustAccounts: u32 = 3;
+    pub const MaxDustQueueLength: u32 = 3;
+    pub const MaxReasonLength: u32 = 8;
+    pub const MaxExpiriesPerBlock: u32 = 2;
 }
 
 thread_local! {
@@ -130,23 +138,53 @@ impl Config for Test {
     type MultiCurrency = Currencies;
     type MinCurrencyDeposits = MinDeposits;
     type Reward = Reward;
+    type NativePriceOracle = PriceOracle;
     type NativeCurrencyId = NativeCurrencyId;
     type BlacklistUpdateOrigin = EnsureRoot<AccountId>;
+    type MaxDustAccounts = MaxDustAccounts;
+    type MaxDustQueueLength = MaxDustQueueLength;
+    type MaxReasonLength = MaxReasonLength;
+    type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
     type WeightInfo = ();
 }
 
+pub struct PriceOracle;
+
+impl NativePriceOracle<AssetId, FixedU128> for PriceOracle {
+    fn price(currency: AssetId) -> Option<FixedU128> {
+        match currency {
+            // 1 native = 0.01 tokens
+            1 => Some(FixedU128::from_rational(1, 100)),
+            2 => Some(FixedU128::one()),
+            _ => None,
+        }
+    }
+}
+
 impl orml_tokens::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type Balance = Balance;
     type Amount = Amount;
     type CurrencyId = AssetId;
-    type WeightInfo = ();
+    type WeightInfo = TokensWeightWithDustQueue<Test, ()>;
     type ExistentialDeposits = ExistentialDeposits;
     type MaxLocks = ();
     type DustRemovalWhitelist = Nothing;
     type ReserveIdentifier = ();
     type MaxReserves = ();
-    type CurrencyHooks = ();
+    type CurrencyHooks = CurrencyHooks;
+}
+
+pub struct CurrencyHooks;
+impl MutationHooks<AccountId, AssetId, Balance> for CurrencyHooks {
+    type OnDust = ();
+    type OnSlash = ();
+    type PreDeposit = ();
+    type PostDeposit = ();
+    type PreTransfer = ();
+    type PostTransfer = Duster;
+    type OnNewTokenAccount = ();
+    type OnKilledTokenAccount = ();
 }
 
 impl pallet_currencies::Config for Test {
@@ -154,7 +192,7 @@ impl pallet_currencies::Config for Test {
     type MultiCurrency = Tokens;
     type NativeCurrency = BasicCurrencyAdapter<Test, Balances, Amount, u32>;
     type GetNativeCurrencyId = NativeCurrencyId;
-    type WeightInfo = ();
+    type WeightInfo = CurrenciesWeightWithDustQueue<Test, ()>;
 }
 
 impl pallet_balances::Config for Test {
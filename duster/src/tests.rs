@@ -1,10 +1,12 @@
 use super::*;
 use crate::mock::{
-    AssetId, Currencies, Duster, ExtBuilder, RuntimeEvent as TestEvent, RuntimeOrigin, System, Test, Tokens, ALICE,
-    BOB, DUSTER, KILLED, TREASURY,
+    AssetId, Currencies, Duster, ExtBuilder, MaxDustAccounts, RuntimeEvent as TestEvent, RuntimeOrigin, System, Test,
+    Tokens, ALICE, BOB, DUSTER, KILLED, TREASURY,
 };
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::traits::Hooks;
+use frame_support::weights::Weight;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
 
 use sp_runtime::traits::BadOrigin;
 
@@ -121,9 +123,9 @@ fn dust_account_native_works() {
             *ALICE,
             currency_id
         ));
-        assert_eq!(Currencies::free_balance(currency_id, &*TREASURY), 990_500);
+        assert_eq!(Currencies::free_balance(currency_id, &*TREASURY), 1_000_000);
 
-        assert_eq!(Currencies::free_balance(0, &*DUSTER), 110_000);
+        assert_eq!(Currencies::free_balance(0, &*DUSTER), 100_500);
 
         assert_eq!(KILLED.with(|r| r.borrow().clone()), vec![*ALICE]);
         for (a, _) in frame_system::Account::<Test>::iter() {
@@ -146,11 +148,11 @@ fn dust_account_native_works() {
                 amount: 500,
             }
             .into(),
-            //reward transfer
+            //reward transfer capped by dust value
             pallet_balances::Event::Transfer {
                 from: *TREASURY,
                 to: *DUSTER,
-                amount: 10_000,
+                amount: 500,
             }
             .into(),
         ]);
@@ -231,11 +233,11 @@ fn native_existential_deposit() {
                 amount: 300,
             }
             .into(),
-            //reward transfer
+            //reward transfer capped by dust value
             pallet_balances::Event::Transfer {
                 from: *TREASURY,
                 to: *DUSTER,
-                amount: 10_000,
+                amount: 300,
             }
             .into(),
         ]);
@@ -317,3 +319,538 @@ fn remove_nondustable_account_works() {
             assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 0),);
         });
 }
+
+#[test]
+fn dust_accounts_works() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .with_balance(*BOB, 1, 200)
+        .with_native_balance(*BOB, 500)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            assert_ok!(Duster::dust_accounts(
+                RuntimeOrigin::signed(*DUSTER),
+                accounts(vec![(*ALICE, 1), (*BOB, 1), (*BOB, 0)])
+            ));
+
+            assert_eq!(Tokens::free_balance(1, &*TREASURY), 300);
+            assert_eq!(Currencies::free_balance(0, &*BOB), 0);
+            // native dust reward is capped by the dust value
+            assert_eq!(Currencies::free_balance(0, &*DUSTER), 20_500);
+
+            System::assert_last_event(
+                Event::BatchDusted {
+                    who: *DUSTER,
+                    dusted: 3,
+                    skipped: 0,
+                }
+                .into(),
+            );
+        });
+}
+
+#[test]
+fn dust_accounts_should_skip_entries_which_cannot_be_dusted() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .with_balance(*BOB, 1, 1_000_000)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            assert_ok!(Duster::dust_accounts(
+                RuntimeOrigin::signed(*DUSTER),
+                accounts(vec![(*ALICE, 1), (*BOB, 1), (*TREASURY, 0)])
+            ));
+
+            assert_eq!(Tokens::free_balance(1, &*TREASURY), 100);
+            assert_eq!(Tokens::free_balance(1, &*BOB), 1_000_000);
+            assert_eq!(Currencies::free_balance(0, &*DUSTER), 10_000);
+
+            System::assert_last_event(
+                Event::BatchDusted {
+                    who: *DUSTER,
+                    dusted: 1,
+                    skipped: 2,
+                }
+                .into(),
+            );
+        });
+}
+
+#[test]
+fn dust_accounts_should_not_accept_more_than_max_dust_accounts() {
+    let accounts: Result<BoundedVec<(u64, AssetId), MaxDustAccounts>, _> =
+        vec![(*ALICE, 1), (*ALICE, 2), (*ALICE, 3), (*ALICE, 4)].try_into();
+    assert!(accounts.is_err());
+}
+
+#[test]
+fn dust_accounts_should_refund_weight_of_skipped_entries() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .with_balance(*BOB, 1, 1_000_000)
+        .build()
+        .execute_with(|| {
+            let batch = accounts(vec![(*ALICE, 1), (*BOB, 1), (*TREASURY, 0)]);
+            let call_weight = <() as WeightInfo>::dust_accounts(3);
+
+            let post_info = Duster::dust_accounts(RuntimeOrigin::signed(*DUSTER), batch).unwrap();
+
+            let skipped_weight = <Test as frame_system::Config>::DbWeight::get()
+                .reads(4)
+                .saturating_mul(2);
+            let expected_weight = <() as WeightInfo>::dust_accounts(1).saturating_add(skipped_weight);
+            assert_eq!(post_info.actual_weight, Some(expected_weight));
+            assert!(expected_weight.all_lt(call_weight));
+        });
+}
+
+#[test]
+fn dust_accounts_should_fail_when_origin_is_not_signed() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            Duster::dust_accounts(RuntimeOrigin::root(), accounts(vec![(*ALICE, 1)])),
+            BadOrigin
+        );
+    });
+}
+
+fn accounts(accounts: Vec<(u64, AssetId)>) -> BoundedVec<(u64, AssetId), MaxDustAccounts> {
+    accounts.try_into().unwrap()
+}
+
+fn queued(account: u64, currency_id: AssetId) -> bool {
+    DustQueue::<Test>::contains_key((account, currency_id))
+}
+
+#[test]
+fn transfer_leaving_dust_should_queue_sender() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *BOB, 1, 150_000));
+
+            assert!(queued(*ALICE, 1));
+            assert!(!queued(*BOB, 1));
+            assert_eq!(Duster::dust_queue_length(), 1);
+
+            // queued only once
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *BOB, 1, 10_000));
+            assert_eq!(Duster::dust_queue_length(), 1);
+        });
+}
+
+#[test]
+fn transfer_weight_should_include_queueing_sender() {
+    assert_eq!(
+        <TokensWeightWithDustQueue<Test, ()> as orml_tokens::WeightInfo>::transfer(),
+        <() as orml_tokens::WeightInfo>::transfer().saturating_add(<() as WeightInfo>::queue_dust_candidate())
+    );
+    assert_eq!(
+        <CurrenciesWeightWithDustQueue<Test, ()> as pallet_currencies::WeightInfo>::transfer_non_native_currency(),
+        <() as pallet_currencies::WeightInfo>::transfer_non_native_currency()
+            .saturating_add(<() as WeightInfo>::queue_dust_candidate())
+    );
+    assert_eq!(
+        <CurrenciesWeightWithDustQueue<Test, ()> as pallet_currencies::WeightInfo>::transfer_native_currency(),
+        <() as pallet_currencies::WeightInfo>::transfer_native_currency()
+    );
+}
+
+#[test]
+fn transfer_should_not_register_extra_block_weight() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *BOB, 1, 150_000));
+
+            assert!(queued(*ALICE, 1));
+            assert_eq!(System::block_weight().total(), Weight::zero());
+        });
+}
+
+#[test]
+fn transfer_from_blacklisted_account_should_not_queue_sender() {
+    ExtBuilder::default()
+        .with_balance(*TREASURY, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*TREASURY), *BOB, 1, 150_000));
+
+            assert!(!queued(*TREASURY, 1));
+            assert_eq!(Duster::dust_queue_length(), 0);
+        });
+}
+
+#[test]
+fn dust_candidates_should_not_be_queued_when_queue_is_full() {
+    let accounts = [*ALICE, *BOB, *DUSTER, 500];
+    accounts
+        .iter()
+        .fold(ExtBuilder::default(), |builder, account| {
+            builder.with_balance(*account, 1, 200_000)
+        })
+        .build()
+        .execute_with(|| {
+            for account in accounts {
+                assert_ok!(Currencies::transfer(RuntimeOrigin::signed(account), 600, 1, 150_000));
+            }
+
+            assert_eq!(Duster::dust_queue_length(), 3);
+            assert!(!queued(500, 1));
+        });
+}
+
+#[test]
+fn on_idle_should_dust_queued_accounts() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *BOB, 1, 150_000));
+
+            Duster::on_idle(1, Weight::MAX);
+
+            assert_eq!(Tokens::free_balance(1, &*ALICE), 0);
+            assert_eq!(Tokens::free_balance(1, &*TREASURY), 50_000);
+            assert!(!queued(*ALICE, 1));
+            assert_eq!(Duster::dust_queue_length(), 0);
+            System::assert_last_event(
+                Event::Dusted {
+                    who: *ALICE,
+                    amount: 50_000,
+                }
+                .into(),
+            );
+        });
+}
+
+#[test]
+fn on_idle_should_remove_entries_which_are_no_longer_dustable() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *BOB, 1, 150_000));
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*BOB), *ALICE, 1, 100_000));
+
+            Duster::on_idle(1, Weight::MAX);
+
+            assert_eq!(Tokens::free_balance(1, &*ALICE), 150_000);
+            assert!(!queued(*ALICE, 1));
+            assert_eq!(Duster::dust_queue_length(), 0);
+        });
+}
+
+#[test]
+fn on_idle_should_respect_remaining_weight() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 200_000)
+        .with_balance(*BOB, 1, 200_000)
+        .build()
+        .execute_with(|| {
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*ALICE), *DUSTER, 1, 150_000));
+            assert_ok!(Currencies::transfer(RuntimeOrigin::signed(*BOB), *DUSTER, 1, 150_000));
+            assert_eq!(Duster::dust_queue_length(), 2);
+
+            let remaining_weight = <() as WeightInfo>::process_dust_queue(1);
+
+            assert_eq!(Duster::on_idle(1, remaining_weight), remaining_weight);
+            assert_eq!(Duster::dust_queue_length(), 1);
+
+            assert_eq!(Duster::on_idle(1, Weight::zero()), Weight::zero());
+            assert_eq!(Duster::dust_queue_length(), 1);
+        });
+}
+
+#[test]
+fn reward_should_be_capped_by_dust_value() {
+    ExtBuilder::default().build().execute_with(|| {
+        // 1 native = 1 token of currency 2
+        assert_eq!(Duster::calculate_reward(2, 100), 100);
+        // 1 native = 0.01 token of currency 1
+        assert_eq!(Duster::calculate_reward(1, 50), 5_000);
+        assert_eq!(Duster::calculate_reward(1, 1_000), 10_000);
+        assert_eq!(Duster::calculate_reward(0, 500), 500);
+    });
+}
+
+#[test]
+fn dust_without_price_should_not_be_rewarded() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(Duster::calculate_reward(3, 1_000_000), 0);
+    });
+}
+
+#[test]
+fn set_reward_policy_works() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let policy = RewardPolicy {
+                reward: 8_000,
+                max_dust_value_share: Permill::from_percent(50),
+            };
+
+            assert_noop!(
+                Duster::set_reward_policy(RuntimeOrigin::signed(*DUSTER), 1, Some(policy)),
+                BadOrigin
+            );
+            assert_ok!(Duster::set_reward_policy(RuntimeOrigin::root(), 1, Some(policy)));
+            assert_eq!(Duster::reward_policy(1), Some(policy));
+            System::assert_last_event(
+                Event::RewardPolicySet {
+                    currency_id: 1,
+                    policy: Some(policy),
+                }
+                .into(),
+            );
+
+            // dust value is 10_000, half of it is 5_000
+            assert_eq!(Duster::calculate_reward(1, 100), 5_000);
+            // half of 20_000 is limited by reward of 8_000
+            assert_eq!(Duster::calculate_reward(1, 200), 8_000);
+
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1));
+            assert_eq!(Currencies::free_balance(0, &*DUSTER), 5_000);
+
+            assert_ok!(Duster::set_reward_policy(RuntimeOrigin::root(), 1, None));
+            assert_eq!(Duster::reward_policy(1), None);
+            assert_eq!(Duster::calculate_reward(1, 100), 10_000);
+        });
+}
+
+#[test]
+fn add_nondustable_entry_should_protect_only_given_currency() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .with_native_balance(*ALICE, 500)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            assert_noop!(
+                Duster::add_nondustable_entry(RuntimeOrigin::signed(*DUSTER), *ALICE, 1, None, b"pool".to_vec()),
+                BadOrigin
+            );
+            assert_ok!(Duster::add_nondustable_entry(
+                RuntimeOrigin::root(),
+                *ALICE,
+                1,
+                None,
+                b"pool".to_vec()
+            ));
+            System::assert_last_event(
+                Event::NonDustableEntryAdded {
+                    who: *ALICE,
+                    currency_id: 1,
+                    expires_at: None,
+                    reason: b"pool".to_vec().try_into().unwrap(),
+                }
+                .into(),
+            );
+
+            assert_noop!(
+                Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1),
+                Error::<Test>::AccountBlacklisted
+            );
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 0));
+            assert_eq!(Currencies::free_balance(0, &*ALICE), 0);
+        });
+}
+
+#[test]
+fn add_nondustable_entry_should_fail_with_invalid_params() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(5);
+
+        assert_noop!(
+            Duster::add_nondustable_entry(RuntimeOrigin::root(), *ALICE, 1, None, b"too long reason".to_vec()),
+            Error::<Test>::ReasonTooLong
+        );
+        assert_noop!(
+            Duster::add_nondustable_entry(RuntimeOrigin::root(), *ALICE, 1, Some(5), vec![]),
+            Error::<Test>::InvalidExpiry
+        );
+
+        assert_ok!(Duster::add_nondustable_entry(
+            RuntimeOrigin::root(),
+            *ALICE,
+            1,
+            Some(10),
+            vec![]
+        ));
+        assert_ok!(Duster::add_nondustable_entry(
+            RuntimeOrigin::root(),
+            *BOB,
+            1,
+            Some(10),
+            vec![]
+        ));
+        assert_noop!(
+            Duster::add_nondustable_entry(RuntimeOrigin::root(), *DUSTER, 1, Some(10), vec![]),
+            Error::<Test>::TooManyExpiries
+        );
+        // replacing an entry frees its expiry slot
+        assert_ok!(Duster::add_nondustable_entry(
+            RuntimeOrigin::root(),
+            *ALICE,
+            1,
+            Some(10),
+            vec![]
+        ));
+        assert_eq!(Duster::nondustable_expiries(10).len(), 2);
+    });
+}
+
+#[test]
+fn nondustable_entry_should_expire_and_be_pruned() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(Duster::add_nondustable_entry(
+                RuntimeOrigin::root(),
+                *ALICE,
+                1,
+                Some(3),
+                b"temp".to_vec()
+            ));
+
+            System::set_block_number(2);
+            assert_noop!(
+                Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1),
+                Error::<Test>::AccountBlacklisted
+            );
+
+            System::set_block_number(3);
+            Duster::on_initialize(3);
+            System::assert_last_event(
+                Event::NonDustableEntryExpired {
+                    who: *ALICE,
+                    currency_id: 1,
+                }
+                .into(),
+            );
+            assert_eq!(Duster::nondustable_entry(*ALICE, 1), None);
+            assert!(Duster::nondustable_expiries(3).is_empty());
+
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1));
+        });
+}
+
+#[test]
+fn expired_entry_should_not_protect_before_pruning() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(Duster::add_nondustable_entry(
+                RuntimeOrigin::root(),
+                *ALICE,
+                1,
+                Some(3),
+                vec![]
+            ));
+
+            System::set_block_number(4);
+            assert!(Duster::nondustable_entry(*ALICE, 1).is_some());
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1));
+        });
+}
+
+#[test]
+fn remove_nondustable_entry_works() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            assert_noop!(
+                Duster::remove_nondustable_entry(RuntimeOrigin::root(), *ALICE, 1),
+                Error::<Test>::NonDustableEntryNotFound
+            );
+
+            assert_ok!(Duster::add_nondustable_entry(
+                RuntimeOrigin::root(),
+                *ALICE,
+                1,
+                Some(3),
+                vec![]
+            ));
+            assert_noop!(
+                Duster::remove_nondustable_entry(RuntimeOrigin::signed(*DUSTER), *ALICE, 1),
+                BadOrigin
+            );
+            assert_ok!(Duster::remove_nondustable_entry(RuntimeOrigin::root(), *ALICE, 1));
+            System::assert_last_event(
+                Event::NonDustableEntryRemoved {
+                    who: *ALICE,
+                    currency_id: 1,
+                }
+                .into(),
+            );
+            assert_eq!(Duster::nondustable_entry(*ALICE, 1), None);
+            assert!(Duster::nondustable_expiries(3).is_empty());
+
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1));
+        });
+}
+
+#[test]
+fn whitelist_should_check_nondustable_entries_of_currency() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_ok!(Duster::add_nondustable_entry(
+            RuntimeOrigin::root(),
+            *ALICE,
+            1,
+            Some(3),
+            b"pool".to_vec()
+        ));
+
+        assert!(!<DusterWhitelist<Test> as Contains<u64>>::contains(&*ALICE));
+        assert!(DusterWhitelist::<Test>::contains(&(*ALICE, 1)));
+        assert!(!DusterWhitelist::<Test>::contains(&(*ALICE, 0)));
+        assert!(DusterWhitelist::<Test>::contains(&(*TREASURY, 0)));
+
+        System::set_block_number(3);
+        assert!(!DusterWhitelist::<Test>::contains(&(*ALICE, 1)));
+    });
+}
+
+#[test]
+fn currency_whitelist_should_add_and_remove_nondustable_entries() {
+    ExtBuilder::default()
+        .with_balance(*ALICE, 1, 100)
+        .build()
+        .execute_with(|| {
+            assert_ok!(<Duster as DustRemovalCurrencyWhitelist<u64, AssetId>>::add_account_currency(&*ALICE, 1));
+
+            assert!(DusterWhitelist::<Test>::contains(&(*ALICE, 1)));
+            assert_noop!(
+                Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1),
+                Error::<Test>::AccountBlacklisted
+            );
+
+            assert_ok!(<Duster as DustRemovalCurrencyWhitelist<u64, AssetId>>::remove_account_currency(&*ALICE, 1));
+            assert_noop!(
+                <Duster as DustRemovalCurrencyWhitelist<u64, AssetId>>::remove_account_currency(&*ALICE, 1),
+                Error::<Test>::NonDustableEntryNotFound
+            );
+
+            assert!(!DusterWhitelist::<Test>::contains(&(*ALICE, 1)));
+            assert_ok!(Duster::dust_account(RuntimeOrigin::signed(*DUSTER), *ALICE, 1));
+        });
+}
@@ -35,6 +35,9 @@
 // --output=weights.rs
 // --template=.maintain/pallet-weight-template.hbs
 
+// NOTE: The weights of `dust_account`, `dust_accounts`, `queue_dust_candidate`, `process_dust_queue`,
+// `set_reward_policy`, `add_nondustable_entry` and `remove_nondustable_entry` are placeholders until the
+// benchmarks in `benchmarking.rs` are run with the command above.
 #![allow(unused_parens)]
 #![allow(unused_imports)]
 #![allow(clippy::unnecessary_cast)]
@@ -48,15 +51,49 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for duster.
 pub trait WeightInfo {
     fn dust_account() -> Weight;
+    fn dust_accounts(n: u32) -> Weight;
+    fn queue_dust_candidate() -> Weight;
+    fn process_dust_queue(n: u32) -> Weight;
     fn add_nondustable_account() -> Weight;
     fn remove_nondustable_account() -> Weight;
+    fn set_reward_policy() -> Weight;
+    fn add_nondustable_entry() -> Weight;
+    fn remove_nondustable_entry() -> Weight;
 }
 
 /// Weights for claims using the hydraDX node and recommended hardware.
 pub struct HydraWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for HydraWeight<T> {
     fn dust_account() -> Weight {
-        Weight::zero()
+        // placeholder, not benchmarked
+        Weight::from_ref_time(64_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(9 as u64))
+            .saturating_add(T::DbWeight::get().writes(5 as u64))
+    }
+
+    fn dust_accounts(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(12_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(64_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads((9 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+    }
+
+    fn queue_dust_candidate() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(18_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(5 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
+
+    fn process_dust_queue(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(6_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(70_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().reads((10 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
     }
 
     fn add_nondustable_account() -> Weight {
@@ -66,12 +103,59 @@ impl<T: frame_system::Config> WeightInfo for HydraWeight<T> {
     fn remove_nondustable_account() -> Weight {
         Weight::zero()
     }
+
+    fn set_reward_policy() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(16_000_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+
+    fn add_nondustable_entry() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(28_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(3 as u64))
+    }
+
+    fn remove_nondustable_entry() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(22_000_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn dust_account() -> Weight {
-        Weight::zero()
+        // placeholder, not benchmarked
+        Weight::from_ref_time(64_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(9 as u64))
+            .saturating_add(RocksDbWeight::get().writes(5 as u64))
+    }
+
+    fn dust_accounts(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(12_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(64_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads((9 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+    }
+
+    fn queue_dust_candidate() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(18_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(5 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
+
+    fn process_dust_queue(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(6_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(70_000_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().reads((10 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes((6 as u64).saturating_mul(n as u64)))
     }
 
     fn add_nondustable_account() -> Weight {
@@ -81,4 +165,23 @@ impl WeightInfo for () {
     fn remove_nondustable_account() -> Weight {
         Weight::zero()
     }
+
+    fn set_reward_policy() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(16_000_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+
+    fn add_nondustable_entry() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(28_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
+    }
+
+    fn remove_nondustable_entry() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(22_000_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
 }
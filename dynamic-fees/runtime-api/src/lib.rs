@@ -0,0 +1,39 @@
+// This file is part of pallet-dynamic-fees.
+
+// Copyright (C) 2020-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the dynamic fees pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_dynamic_fees::types::FeeEntry;
+
+sp_api::decl_runtime_apis! {
+    pub trait DynamicFeesApi<AssetId, Fee, BlockNumber>
+    where
+        AssetId: Codec,
+        Fee: Codec,
+        BlockNumber: Codec,
+    {
+        /// Return the last calculated fee entry of `asset_id`. `None` if the fees of the asset were never updated.
+        fn current_fees(asset_id: AssetId) -> Option<FeeEntry<Fee, BlockNumber>>;
+
+        /// Return `(asset fee, protocol fee)` the next trade of `asset_id` would pay if it was executed in
+        /// `block_number`, based on the current oracle values.
+        fn simulate_fees(asset_id: AssetId, block_number: BlockNumber) -> (Fee, Fee);
+    }
+}
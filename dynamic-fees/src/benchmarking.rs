@@ -0,0 +1,49 @@
+// This file is part of pallet-dynamic-fees.
+
+// Copyright (C) 2020-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::benchmarks;
+use sp_runtime::FixedU128;
+
+benchmarks! {
+    where_clause { where
+        T::AssetId: From<u32>,
+    }
+
+    set_fee_params {
+        let asset_id: T::AssetId = 1_000u32.into();
+        let fee_params = FeeParams {
+            min_fee: T::Fee::from_percent(1),
+            max_fee: T::Fee::from_percent(40),
+            decay: FixedU128::from_rational(1, 100),
+            amplification: FixedU128::from(2),
+        };
+        let params = FeeParamsOverride {
+            asset_fee_params: fee_params,
+            protocol_fee_params: fee_params,
+        };
+        let successful_origin = T::AuthorityOrigin::try_successful_origin().unwrap();
+    }: _<T::RuntimeOrigin>(successful_origin, asset_id, Some(params))
+    verify {
+        assert_eq!(AssetFeeParamsOverride::<T>::get(asset_id), Some(params));
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::tests::mock::ExtBuilder::default().build(), crate::tests::mock::Test);
+}
@@ -34,12 +34,17 @@
 //! * **Asset fee amplification:** The amplification parameter for asset fee.
 //! * **Protocol fee amplification:** The amplification parameter for protocol fee.
 //! * **Minimum and maximum fee:** The minimum and maximum fee value for asset or protocol fee.
+//! * **Volume oracle period:** The oracle period of the volume and liquidity the fees are calculated from.
+//! * **Volatility:** Optional asset fee component based on the divergence of a short and a long average price.
 //!
 //! ### Storage
 //!
 //! The module stores last calculated fees as tuple of `(Fee, Fee, Block number)` where the first item is asset fee,
 //! the second one is protocol fee and the third one is block number indicating when the two fees were updated.
 //!
+//! Fee parameters of individual assets can be overridden by `AuthorityOrigin` via `set_fee_params`. Assets without
+//! an override use the global `AssetFeeParameters` and `ProtocolFeeParameters`.
+//!
 //! ## Interface
 //!
 //! ### Update and retrieve fee
@@ -48,6 +53,21 @@
 //! This can be used to integrate the dynamic fee mechanism where desired.
 //!
 //! On first retrieve call in a block, the asset fee as well as the protocol are updated and new fees are returned.
+//! If `VolatilityParameters` are configured, the relative divergence of the short and long average price of the asset
+//! multiplied by the volatility amplification is added to the returned asset fee, limited by the maximum asset fee.
+//! The stored asset fee does not include this surcharge so that it does not compound from block to block.
+//! A `FeesUpdated` event is emitted whenever the fees of an asset change.
+//!
+//! ### Runtime API
+//!
+//! The current fee entry of an asset and the fees the next trade would pay at a given block can be queried via the
+//! `DynamicFeesApi` runtime API defined in the `pallet-dynamic-fees-runtime-api` crate.
+//!
+//! ### Simulation
+//!
+//! With the `std` feature the `simulation` module replays a sequence of oracle observations offline and outputs the
+//! fee trajectory as CSV, which helps with tuning the fee parameters. It uses the same fee calculation as the pallet,
+//! including the optional volatility component, with the fee parameters of the simulated asset passed explicitly.
 //!
 //! ### Prerequisites
 //!
@@ -60,17 +80,27 @@ use orml_traits::GetByKey;
 use sp_runtime::traits::{BlockNumberProvider, Saturating};
 use sp_runtime::{FixedPointOperand, PerThing, SaturatedConversion};
 
+#[cfg(feature = "std")]
+pub mod simulation;
 #[cfg(test)]
 mod tests;
 pub mod traits;
 pub mod types;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 pub use pallet::*;
 
-use crate::traits::{Volume, VolumeProvider};
-use crate::types::{FeeEntry, FeeParams};
+use crate::traits::{PriceProvider, Volume, VolumeProvider};
+use crate::types::{FeeEntry, FeeParams, FeeParamsOverride, VolatilityParams};
 use hydra_dx_math::dynamic_fees::types::OracleEntry;
 use hydra_dx_math::dynamic_fees::{recalculate_asset_fee, recalculate_protocol_fee};
+use hydradx_traits::OraclePeriod;
+use sp_runtime::traits::{CheckedDiv, One, Zero};
+use sp_runtime::{FixedPointNumber, FixedU128};
+use weights::WeightInfo;
 
 type Balance = u128;
 
@@ -80,8 +110,8 @@ pub mod pallet {
     use crate::traits::VolumeProvider;
     use crate::types::FeeEntry;
     use frame_support::pallet_prelude::*;
-    use frame_system::pallet_prelude::BlockNumberFor;
-    use sp_runtime::traits::{BlockNumberProvider, Zero};
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
+    use sp_runtime::traits::BlockNumberProvider;
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
@@ -91,9 +121,16 @@ pub mod pallet {
     #[pallet::getter(fn current_fees)]
     /// Stores last calculated fee of an asset and block number in which it was changed..
     /// Stored as (Asset fee, Protocol fee, Block number)
+    /// The asset fee does not include the volatility component.
     pub type AssetFee<T: Config> =
         StorageMap<_, Twox64Concat, T::AssetId, FeeEntry<T::Fee, T::BlockNumber>, OptionQuery>;
 
+    #[pallet::storage]
+    #[pallet::getter(fn fee_params_override)]
+    /// Asset specific fee parameters used instead of the global ones.
+    pub type AssetFeeParamsOverride<T: Config> =
+        StorageMap<_, Twox64Concat, T::AssetId, FeeParamsOverride<T::Fee>, OptionQuery>;
+
     #[pallet::config]
     pub trait Config: frame_system::Config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -110,21 +147,84 @@ pub mod pallet {
         /// Volume provider implementation
         type Oracle: VolumeProvider<Self::AssetId, Balance>;
 
+        /// Oracle period of the volume and liquidity used to calculate the fees.
+        #[pallet::constant]
+        type VolumeOraclePeriod: Get<OraclePeriod>;
+
+        /// Price provider used to calculate the volatility component of the asset fee.
+        type PriceOracle: PriceProvider<Self::AssetId>;
+
+        /// Parameters of the volatility component of the asset fee. `None` disables the component.
+        #[pallet::constant]
+        type VolatilityParameters: Get<Option<VolatilityParams>>;
+
         #[pallet::constant]
         type AssetFeeParameters: Get<FeeParams<Self::Fee>>;
 
         #[pallet::constant]
         type ProtocolFeeParameters: Get<FeeParams<Self::Fee>>;
+
+        /// Origin able to set asset specific fee parameters.
+        type AuthorityOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
     }
 
     #[pallet::event]
-    pub enum Event<T: Config> {}
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Asset and protocol fee of an asset changed.
+        FeesUpdated {
+            asset_id: T::AssetId,
+            asset_fee: T::Fee,
+            protocol_fee: T::Fee,
+        },
+        /// Fee parameters of an asset were set or removed.
+        FeeParamsSet {
+            asset_id: T::AssetId,
+            params: Option<FeeParamsOverride<T::Fee>>,
+        },
+    }
 
     #[pallet::error]
-    pub enum Error<T> {}
+    pub enum Error<T> {
+        /// Minimum fee is greater than maximum fee or amplification is zero.
+        InvalidFeeParams,
+    }
 
     #[pallet::call]
-    impl<T: Config> Pallet<T> {}
+    impl<T: Config> Pallet<T> {
+        /// Set or remove the fee parameters of `asset_id` overriding the global ones.
+        ///
+        /// Parameters:
+        /// - `origin`: Must be `T::AuthorityOrigin`
+        /// - `asset_id`: Asset id
+        /// - `params`: New fee parameters or `None` to fall back to the global ones
+        ///
+        /// Emits `FeeParamsSet` event if successful.
+        #[pallet::call_index(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_fee_params())]
+        pub fn set_fee_params(
+            origin: OriginFor<T>,
+            asset_id: T::AssetId,
+            params: Option<FeeParamsOverride<T::Fee>>,
+        ) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            if let Some(params) = params {
+                ensure!(
+                    params.asset_fee_params.is_valid() && params.protocol_fee_params.is_valid(),
+                    Error::<T>::InvalidFeeParams
+                );
+            }
+
+            AssetFeeParamsOverride::<T>::set(asset_id, params);
+
+            Self::deposit_event(Event::FeeParamsSet { asset_id, params });
+            Ok(())
+        }
+    }
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
@@ -158,62 +258,187 @@ where
     fn update_fee(asset_id: T::AssetId) -> (T::Fee, T::Fee) {
         let block_number = T::BlockNumberProvider::current_block_number();
 
-        let asset_fee_params = T::AssetFeeParameters::get();
-        let protocol_fee_params = T::ProtocolFeeParameters::get();
-
-        let current_fee_entry = Self::current_fees(asset_id).unwrap_or(FeeEntry {
-            asset_fee: asset_fee_params.min_fee,
-            protocol_fee: protocol_fee_params.min_fee,
-            timestamp: block_number,
-        });
+        let current_fee_entry = Self::current_fee_entry(asset_id, block_number);
+        let volatility_fee = Self::volatility_fee(asset_id);
+        let current_fees = Self::with_volatility_fee(asset_id, &current_fee_entry, volatility_fee);
 
         // Update only if it has not yet been updated this block
         if block_number == current_fee_entry.timestamp {
-            return (current_fee_entry.asset_fee, current_fee_entry.protocol_fee);
+            return current_fees;
         }
 
+        let Some(fee_entry) = Self::calculate_fees(asset_id, &current_fee_entry, block_number) else {
+            return current_fees;
+        };
+
+        AssetFee::<T>::insert(asset_id, fee_entry);
+
+        let fees = Self::with_volatility_fee(asset_id, &fee_entry, volatility_fee);
+        if fees != current_fees {
+            Self::deposit_event(Event::FeesUpdated {
+                asset_id,
+                asset_fee: fees.0,
+                protocol_fee: fees.1,
+            });
+        }
+        fees
+    }
+
+    /// Returns `(asset fee, protocol fee)` the next trade of `asset_id` would pay if it was executed in `block_number`.
+    ///
+    /// The fees are calculated from the current oracle values without being stored.
+    pub fn simulate_fees(asset_id: T::AssetId, block_number: T::BlockNumber) -> (T::Fee, T::Fee) {
+        let current_fee_entry = Self::current_fee_entry(asset_id, block_number);
+        let volatility_fee = Self::volatility_fee(asset_id);
+
+        let fee_entry = if block_number <= current_fee_entry.timestamp {
+            current_fee_entry
+        } else {
+            Self::calculate_fees(asset_id, &current_fee_entry, block_number).unwrap_or(current_fee_entry)
+        };
+        Self::with_volatility_fee(asset_id, &fee_entry, volatility_fee)
+    }
+
+    /// Returns `(asset fee, protocol fee)` of `fee_entry` with `volatility_fee` added to the asset fee, limited by
+    /// the maximum asset fee.
+    fn with_volatility_fee(
+        asset_id: T::AssetId,
+        fee_entry: &FeeEntry<T::Fee, T::BlockNumber>,
+        volatility_fee: T::Fee,
+    ) -> (T::Fee, T::Fee) {
+        if volatility_fee.is_zero() {
+            return (fee_entry.asset_fee, fee_entry.protocol_fee);
+        }
+        let (asset_fee_params, _) = Self::fee_params(asset_id);
+        (
+            add_volatility_fee(fee_entry.asset_fee, volatility_fee, asset_fee_params.max_fee),
+            fee_entry.protocol_fee,
+        )
+    }
+
+    /// Returns the volatility component of the asset fee.
+    ///
+    /// Zero if the component is disabled or the prices of the asset are not available.
+    fn volatility_fee(asset_id: T::AssetId) -> T::Fee {
+        let Some(params) = T::VolatilityParameters::get() else {
+            return T::Fee::zero();
+        };
+        let (Some(short_price), Some(long_price)) = (
+            T::PriceOracle::asset_price(asset_id, params.short_period),
+            T::PriceOracle::asset_price(asset_id, params.long_period),
+        ) else {
+            return T::Fee::zero();
+        };
+        calculate_volatility_fee(short_price, long_price, params.amplification)
+    }
+
+    /// Returns the fee parameters of an asset as `(asset fee params, protocol fee params)`.
+    ///
+    /// Asset specific parameters take precedence over the global ones.
+    pub fn fee_params(asset_id: T::AssetId) -> (FeeParams<T::Fee>, FeeParams<T::Fee>) {
+        Self::fee_params_override(asset_id)
+            .map(|params| (params.asset_fee_params, params.protocol_fee_params))
+            .unwrap_or_else(|| (T::AssetFeeParameters::get(), T::ProtocolFeeParameters::get()))
+    }
+
+    /// Returns the stored fee entry of an asset or an entry with the minimum fees at `block_number` if there is none.
+    fn current_fee_entry(asset_id: T::AssetId, block_number: T::BlockNumber) -> FeeEntry<T::Fee, T::BlockNumber> {
+        Self::current_fees(asset_id).unwrap_or_else(|| {
+            let (asset_fee_params, protocol_fee_params) = Self::fee_params(asset_id);
+            FeeEntry {
+                asset_fee: asset_fee_params.min_fee,
+                protocol_fee: protocol_fee_params.min_fee,
+                timestamp: block_number,
+            }
+        })
+    }
+
+    /// Calculates the fees at `block_number` based on `current_fee_entry` and the current oracle values.
+    ///
+    /// The volatility component is not included in the returned asset fee, see `with_volatility_fee`.
+    ///
+    /// Returns `None` if the oracle does not provide volume or liquidity of the asset.
+    fn calculate_fees(
+        asset_id: T::AssetId,
+        current_fee_entry: &FeeEntry<T::Fee, T::BlockNumber>,
+        block_number: T::BlockNumber,
+    ) -> Option<FeeEntry<T::Fee, T::BlockNumber>> {
+        let (asset_fee_params, protocol_fee_params) = Self::fee_params(asset_id);
+
         let delta_blocks: u128 = block_number
             .saturating_sub(current_fee_entry.timestamp)
             .saturated_into();
 
-        let Some(volume) = T::Oracle::asset_volume(asset_id) else {
-            return (current_fee_entry.asset_fee, current_fee_entry.protocol_fee);
-        };
-        let Some(liquidity) = T::Oracle::asset_liquidity(asset_id) else {
-            return (current_fee_entry.asset_fee, current_fee_entry.protocol_fee);
-        };
+        let period = T::VolumeOraclePeriod::get();
+        let volume = T::Oracle::asset_volume(asset_id, period)?;
+        let liquidity = T::Oracle::asset_liquidity(asset_id, period)?;
 
-        let asset_fee = recalculate_asset_fee(
+        let (asset_fee, protocol_fee) = recalculate_fees(
             OracleEntry {
                 amount_in: volume.amount_in(),
                 amount_out: volume.amount_out(),
                 liquidity,
             },
-            current_fee_entry.asset_fee,
+            (current_fee_entry.asset_fee, current_fee_entry.protocol_fee),
             delta_blocks,
-            asset_fee_params.into(),
-        );
-        let protocol_fee = recalculate_protocol_fee(
-            OracleEntry {
-                amount_in: volume.amount_in(),
-                amount_out: volume.amount_out(),
-                liquidity,
-            },
-            current_fee_entry.protocol_fee,
-            delta_blocks,
-            protocol_fee_params.into(),
+            asset_fee_params,
+            protocol_fee_params,
         );
 
-        AssetFee::<T>::insert(
-            asset_id,
-            FeeEntry {
-                asset_fee,
-                protocol_fee,
-                timestamp: block_number,
-            },
-        );
-        (asset_fee, protocol_fee)
+        Some(FeeEntry {
+            asset_fee,
+            protocol_fee,
+            timestamp: block_number,
+        })
+    }
+}
+
+/// Recalculates `(asset fee, protocol fee)` from `current_fees` after `delta_blocks` blocks based on the oracle
+/// values in `entry`. The volatility component is not included.
+///
+/// Shared by the pallet and the `simulation` module.
+pub fn recalculate_fees<Fee>(
+    entry: OracleEntry,
+    current_fees: (Fee, Fee),
+    delta_blocks: u128,
+    asset_fee_params: FeeParams<Fee>,
+    protocol_fee_params: FeeParams<Fee>,
+) -> (Fee, Fee)
+where
+    Fee: PerThing,
+    <Fee as PerThing>::Inner: FixedPointOperand,
+{
+    let asset_fee = recalculate_asset_fee(entry.clone(), current_fees.0, delta_blocks, asset_fee_params.into());
+    let protocol_fee = recalculate_protocol_fee(entry, current_fees.1, delta_blocks, protocol_fee_params.into());
+    (asset_fee, protocol_fee)
+}
+
+/// Returns the volatility component of the asset fee, i.e. the relative divergence of `short_price` and `long_price`
+/// multiplied by `amplification`, limited to 100%.
+pub fn calculate_volatility_fee<Fee: PerThing>(
+    short_price: FixedU128,
+    long_price: FixedU128,
+    amplification: FixedU128,
+) -> Fee {
+    let divergence = if short_price > long_price {
+        short_price.saturating_sub(long_price)
+    } else {
+        long_price.saturating_sub(short_price)
+    };
+    let volatility_fee = divergence
+        .checked_div(&long_price)
+        .unwrap_or_default()
+        .saturating_mul(amplification)
+        .min(FixedU128::one());
+    Fee::from_rational(volatility_fee.into_inner(), FixedU128::DIV)
+}
+
+/// Adds `volatility_fee` to `asset_fee`, limited by `max_fee`.
+pub fn add_volatility_fee<Fee: PerThing>(asset_fee: Fee, volatility_fee: Fee, max_fee: Fee) -> Fee {
+    if volatility_fee.is_zero() {
+        return asset_fee;
     }
+    asset_fee.saturating_add(volatility_fee).min(max_fee)
 }
 
 pub struct UpdateAndRetrieveFees<T: Config>(sp_std::marker::PhantomData<T>);
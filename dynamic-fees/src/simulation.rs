@@ -0,0 +1,173 @@
+// This file is part of pallet-dynamic-fees.
+
+// Copyright (C) 2020-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline simulation of the fee curves.
+//!
+//! Replays a sequence of oracle observations through the same fee recalculation the pallet uses and
+//! returns the resulting fee trajectory, e.g. to tune fee parameters before proposing them on chain.
+//! Observations and trajectories can be read from and written to CSV.
+//!
+//! The fee parameters are passed explicitly, so to simulate an asset with overridden fee parameters pass
+//! the parameters returned by `Pallet::fee_params` for that asset.
+
+use crate::types::FeeParams;
+use crate::{add_volatility_fee, calculate_volatility_fee, recalculate_fees};
+use hydra_dx_math::dynamic_fees::types::OracleEntry;
+use sp_runtime::{FixedPointNumber, FixedPointOperand, FixedU128, PerThing};
+
+pub type Balance = u128;
+
+/// Oracle values observed in `block`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Observation {
+    pub block: u64,
+    pub amount_in: Balance,
+    pub amount_out: Balance,
+    pub liquidity: Balance,
+    /// Short and long average price of the asset used for the volatility component, if available.
+    pub prices: Option<(FixedU128, FixedU128)>,
+}
+
+/// Fees returned by the update in `block`. The asset fee includes the volatility component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeePoint<Fee> {
+    pub block: u64,
+    pub asset_fee: Fee,
+    pub protocol_fee: Fee,
+}
+
+/// Replays `observations` starting from `initial_fees` (asset fee, protocol fee) set in `initial_block`.
+///
+/// Like in the pallet the fees are updated at most once per block, observations of blocks that are not after the
+/// previous update are skipped. If `volatility_amplification` is set, the volatility component calculated from the
+/// prices of an observation is added to the returned asset fee but, like in the pallet, not carried over to the
+/// next block.
+pub fn simulate<Fee>(
+    asset_fee_params: FeeParams<Fee>,
+    protocol_fee_params: FeeParams<Fee>,
+    volatility_amplification: Option<FixedU128>,
+    initial_fees: (Fee, Fee),
+    initial_block: u64,
+    observations: &[Observation],
+) -> Vec<FeePoint<Fee>>
+where
+    Fee: PerThing,
+    <Fee as PerThing>::Inner: FixedPointOperand,
+{
+    let mut block = initial_block;
+    let mut fees = initial_fees;
+    let mut trajectory = Vec::with_capacity(observations.len());
+    for observation in observations {
+        if observation.block <= block {
+            continue;
+        }
+        let delta_blocks = (observation.block - block) as u128;
+        let entry = OracleEntry {
+            amount_in: observation.amount_in,
+            amount_out: observation.amount_out,
+            liquidity: observation.liquidity,
+        };
+        block = observation.block;
+        fees = recalculate_fees(entry, fees, delta_blocks, asset_fee_params, protocol_fee_params);
+
+        let volatility_fee = match (volatility_amplification, observation.prices) {
+            (Some(amplification), Some((short_price, long_price))) => {
+                calculate_volatility_fee(short_price, long_price, amplification)
+            }
+            _ => Fee::zero(),
+        };
+        trajectory.push(FeePoint {
+            block,
+            asset_fee: add_volatility_fee(fees.0, volatility_fee, asset_fee_params.max_fee),
+            protocol_fee: fees.1,
+        });
+    }
+    trajectory
+}
+
+/// Parses observations from CSV with the header `block,amount_in,amount_out,liquidity` or
+/// `block,amount_in,amount_out,liquidity,short_price,long_price`.
+///
+/// Prices are given as the inner value of `FixedU128`, i.e. with 18 decimals.
+pub fn observations_from_csv(input: &str) -> Result<Vec<Observation>, String> {
+    let mut lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+    let with_prices = match lines.next() {
+        Some("block,amount_in,amount_out,liquidity") => false,
+        Some("block,amount_in,amount_out,liquidity,short_price,long_price") => true,
+        header => return Err(format!("unexpected header: {header:?}")),
+    };
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let values = line
+                .split(',')
+                .map(|value| value.trim().parse::<u128>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("line {}: {e}", i + 2))?;
+            let (block, amount_in, amount_out, liquidity, prices) = match (with_prices, &values[..]) {
+                (false, [block, amount_in, amount_out, liquidity]) => {
+                    (*block, *amount_in, *amount_out, *liquidity, None)
+                }
+                (true, [block, amount_in, amount_out, liquidity, short_price, long_price]) => (
+                    *block,
+                    *amount_in,
+                    *amount_out,
+                    *liquidity,
+                    Some((FixedU128::from_inner(*short_price), FixedU128::from_inner(*long_price))),
+                ),
+                _ => {
+                    let expected = if with_prices { 6 } else { 4 };
+                    return Err(format!(
+                        "line {}: expected {expected} values, got {}",
+                        i + 2,
+                        values.len()
+                    ));
+                }
+            };
+            Ok(Observation {
+                block: u64::try_from(block).map_err(|e| format!("line {}: {e}", i + 2))?,
+                amount_in,
+                amount_out,
+                liquidity,
+                prices,
+            })
+        })
+        .collect()
+}
+
+/// Formats `trajectory` as CSV with the header `block,asset_fee,protocol_fee`.
+///
+/// Fees are written as exact decimal fractions so the output is reproducible.
+pub fn trajectory_to_csv<Fee: PerThing>(trajectory: &[FeePoint<Fee>]) -> String {
+    let mut csv = String::from("block,asset_fee,protocol_fee\n");
+    for point in trajectory {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            point.block,
+            fee_to_decimal(point.asset_fee),
+            fee_to_decimal(point.protocol_fee)
+        ));
+    }
+    csv
+}
+
+fn fee_to_decimal<Fee: PerThing>(fee: Fee) -> String {
+    let parts: u128 = fee.deconstruct().into();
+    let accuracy: u128 = Fee::ACCURACY.into();
+    let decimals = accuracy.to_string().len() - 1;
+    format!("{}.{:0decimals$}", parts / accuracy, parts % accuracy)
+}
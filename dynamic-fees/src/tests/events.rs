@@ -0,0 +1,129 @@
+use crate::tests::mock::*;
+use crate::tests::oracle::SingleValueOracle;
+use crate::types::FeeEntry;
+use crate::Event;
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::FixedU128;
+
+fn fee_events() -> Vec<RuntimeEvent> {
+    System::events()
+        .into_iter()
+        .map(|record| record.event)
+        .filter(|event| matches!(event, RuntimeEvent::DynamicFees(_)))
+        .collect()
+}
+
+#[test]
+fn fees_updated_event_should_be_emitted_when_fees_change() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .with_asset_fee_params(
+            Fee::from_percent(1),
+            Fee::from_percent(40),
+            FixedU128::zero(),
+            FixedU128::one(),
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let (asset_fee, protocol_fee) = retrieve_fee_entry(HDX);
+
+            assert_eq!(
+                fee_events(),
+                vec![Event::<Test>::FeesUpdated {
+                    asset_id: HDX,
+                    asset_fee,
+                    protocol_fee,
+                }
+                .into()]
+            );
+        });
+}
+
+#[test]
+fn fees_updated_event_should_be_emitted_once_per_block() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            retrieve_fee_entry(HDX);
+            retrieve_fee_entry(HDX);
+
+            assert_eq!(fee_events().len(), 1);
+        });
+}
+
+#[test]
+fn fees_updated_event_should_not_be_emitted_when_fees_do_not_change() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(20), Fee::from_percent(20), 0)
+        .with_asset_fee_params(
+            Fee::from_percent(1),
+            Fee::from_percent(40),
+            FixedU128::zero(),
+            FixedU128::one(),
+        )
+        .with_protocol_fee_params(
+            Fee::from_percent(1),
+            Fee::from_percent(40),
+            FixedU128::zero(),
+            FixedU128::one(),
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            retrieve_fee_entry(HDX);
+
+            assert!(fee_events().is_empty());
+        });
+}
+
+#[test]
+fn simulate_fees_should_return_fees_of_next_trade_without_storing_them() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .with_asset_fee_params(
+            Fee::from_percent(1),
+            Fee::from_percent(40),
+            FixedU128::zero(),
+            FixedU128::one(),
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let simulated = DynamicFees::simulate_fees(HDX, 1);
+
+            assert_eq!(simulated.0, Fee::from_percent(4));
+            assert_eq!(
+                DynamicFees::current_fees(HDX),
+                Some(FeeEntry {
+                    asset_fee: Fee::from_percent(2),
+                    protocol_fee: Fee::zero(),
+                    timestamp: 0,
+                })
+            );
+            assert!(fee_events().is_empty());
+
+            assert_eq!(retrieve_fee_entry(HDX), simulated);
+        });
+}
+
+#[test]
+fn simulate_fees_should_return_current_fees_when_already_updated_in_block() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 5)
+        .build()
+        .execute_with(|| {
+            assert_eq!(DynamicFees::simulate_fees(HDX, 5), (Fee::from_percent(2), Fee::zero()));
+        });
+}
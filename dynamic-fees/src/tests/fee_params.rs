@@ -0,0 +1,137 @@
+use crate::tests::mock::*;
+use crate::tests::oracle::SingleValueOracle;
+use crate::types::{FeeParams, FeeParamsOverride};
+use crate::{Error, Event};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::{DispatchError::BadOrigin, FixedU128};
+
+fn fee_params(min_fee: Fee, max_fee: Fee) -> FeeParams<Fee> {
+    FeeParams {
+        min_fee,
+        max_fee,
+        decay: FixedU128::zero(),
+        amplification: FixedU128::one(),
+    }
+}
+
+fn stablecoin_params() -> FeeParamsOverride<Fee> {
+    FeeParamsOverride {
+        asset_fee_params: fee_params(Fee::from_float(0.0005), Fee::from_percent(1)),
+        protocol_fee_params: fee_params(Fee::zero(), Fee::from_float(0.0005)),
+    }
+}
+
+#[test]
+fn set_fee_params_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+
+        assert_ok!(DynamicFees::set_fee_params(
+            RuntimeOrigin::root(),
+            HDX,
+            Some(stablecoin_params())
+        ));
+
+        assert_eq!(DynamicFees::fee_params_override(HDX), Some(stablecoin_params()));
+        assert_eq!(
+            DynamicFees::fee_params(HDX),
+            (
+                stablecoin_params().asset_fee_params,
+                stablecoin_params().protocol_fee_params
+            )
+        );
+        System::assert_last_event(
+            Event::<Test>::FeeParamsSet {
+                asset_id: HDX,
+                params: Some(stablecoin_params()),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn removing_fee_params_should_fall_back_to_global_params() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(DynamicFees::set_fee_params(
+            RuntimeOrigin::root(),
+            HDX,
+            Some(stablecoin_params())
+        ));
+        assert_ok!(DynamicFees::set_fee_params(RuntimeOrigin::root(), HDX, None));
+
+        assert_eq!(DynamicFees::fee_params_override(HDX), None);
+        assert_eq!(
+            DynamicFees::fee_params(HDX),
+            (AssetFeeParams::get(), ProtocolFeeParams::get())
+        );
+    });
+}
+
+#[test]
+fn set_fee_params_should_fail_when_origin_is_not_authority() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            DynamicFees::set_fee_params(RuntimeOrigin::signed(1), HDX, Some(stablecoin_params())),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn set_fee_params_should_fail_when_params_are_invalid() {
+    ExtBuilder::default().build().execute_with(|| {
+        let mut params = stablecoin_params();
+        params.asset_fee_params.min_fee = Fee::from_percent(2);
+        assert_noop!(
+            DynamicFees::set_fee_params(RuntimeOrigin::root(), HDX, Some(params)),
+            Error::<Test>::InvalidFeeParams
+        );
+
+        let mut params = stablecoin_params();
+        params.protocol_fee_params.amplification = FixedU128::zero();
+        assert_noop!(
+            DynamicFees::set_fee_params(RuntimeOrigin::root(), HDX, Some(params)),
+            Error::<Test>::InvalidFeeParams
+        );
+    });
+}
+
+#[test]
+fn fees_should_be_limited_by_asset_specific_params() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .build()
+        .execute_with(|| {
+            assert_ok!(DynamicFees::set_fee_params(
+                RuntimeOrigin::root(),
+                HDX,
+                Some(stablecoin_params())
+            ));
+
+            System::set_block_number(1);
+            let fee = retrieve_fee_entry(HDX);
+            assert_eq!(fee.0, Fee::from_percent(1));
+        });
+}
+
+#[test]
+fn fees_of_assets_without_override_should_use_global_params() {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .build()
+        .execute_with(|| {
+            assert_ok!(DynamicFees::set_fee_params(
+                RuntimeOrigin::root(),
+                HDX + 1,
+                Some(stablecoin_params())
+            ));
+
+            System::set_block_number(1);
+            let fee = retrieve_fee_entry(HDX);
+            assert_eq!(fee.0, Fee::from_percent(4));
+        });
+}
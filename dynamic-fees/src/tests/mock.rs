@@ -20,12 +20,13 @@
 use sp_std::prelude::*;
 use std::cell::RefCell;
 
-use crate::{Config, UpdateAndRetrieveFees, Volume, VolumeProvider};
+use crate::{Config, PriceProvider, UpdateAndRetrieveFees, Volume, VolumeProvider};
 
 use frame_support::{
     construct_runtime, parameter_types,
     traits::{ConstU32, ConstU64},
 };
+use frame_system::EnsureRoot;
 use orml_traits::GetByKey;
 pub use orml_traits::MultiCurrency;
 use sp_core::H256;
@@ -38,7 +39,8 @@ use sp_runtime::{
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 use crate::tests::oracle::Oracle;
-use crate::types::{FeeEntry, FeeParams};
+use crate::types::{FeeEntry, FeeParams, VolatilityParams};
+use hydradx_traits::OraclePeriod;
 use sp_runtime::traits::{One, Zero};
 
 pub type Balance = u128;
@@ -56,6 +58,10 @@ thread_local! {
     pub static BLOCK: RefCell<usize> = RefCell::new(0);
     pub static ASSET_FEE_PARAMS: RefCell<FeeParams<Fee>> = RefCell::new(fee_params_default());
     pub static PROTOCOL_FEE_PARAMS: RefCell<FeeParams<Fee>> = RefCell::new(fee_params_default());
+    pub static VOLUME_PERIOD: RefCell<OraclePeriod> = RefCell::new(OraclePeriod::Short);
+    pub static REQUESTED_PERIOD: RefCell<Option<OraclePeriod>> = RefCell::new(None);
+    pub static VOLATILITY_PARAMS: RefCell<Option<VolatilityParams>> = RefCell::new(None);
+    pub static PRICES: RefCell<Vec<(OraclePeriod, FixedU128)>> = RefCell::new(vec![]);
 }
 
 fn fee_params_default() -> FeeParams<Fee> {
@@ -108,6 +114,8 @@ impl frame_system::Config for Test {
 parameter_types! {
     pub AssetFeeParams: FeeParams<Fee>= ASSET_FEE_PARAMS.with(|v| *v.borrow());
     pub ProtocolFeeParams: FeeParams<Fee>= PROTOCOL_FEE_PARAMS.with(|v| *v.borrow());
+    pub VolumePeriod: OraclePeriod = VOLUME_PERIOD.with(|v| *v.borrow());
+    pub VolatilityParameters: Option<VolatilityParams> = VOLATILITY_PARAMS.with(|v| *v.borrow());
 }
 
 impl Config for Test {
@@ -116,8 +124,13 @@ impl Config for Test {
     type AssetId = AssetId;
     type BlockNumberProvider = System;
     type Oracle = OracleProvider;
+    type VolumeOraclePeriod = VolumePeriod;
+    type PriceOracle = PriceOracleProvider;
+    type VolatilityParameters = VolatilityParameters;
     type AssetFeeParameters = AssetFeeParams;
     type ProtocolFeeParameters = ProtocolFeeParams;
+    type AuthorityOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
 }
 
 pub struct ExtBuilder {
@@ -129,6 +142,10 @@ impl Default for ExtBuilder {
         ORACLE.with(|v| {
             *v.borrow_mut() = Box::new(Oracle::new());
         });
+        VOLUME_PERIOD.with(|v| *v.borrow_mut() = OraclePeriod::Short);
+        REQUESTED_PERIOD.with(|v| *v.borrow_mut() = None);
+        VOLATILITY_PARAMS.with(|v| *v.borrow_mut() = None);
+        PRICES.with(|v| v.borrow_mut().clear());
 
         Self { initial_fee: None }
     }
@@ -174,6 +191,17 @@ impl ExtBuilder {
         self
     }
 
+    pub fn with_volume_period(self, period: OraclePeriod) -> Self {
+        VOLUME_PERIOD.with(|v| *v.borrow_mut() = period);
+        self
+    }
+
+    pub fn with_volatility(self, params: VolatilityParams, prices: Vec<(OraclePeriod, FixedU128)>) -> Self {
+        VOLATILITY_PARAMS.with(|v| *v.borrow_mut() = Some(params));
+        PRICES.with(|v| *v.borrow_mut() = prices);
+        self
+    }
+
     pub fn with_initial_fees(mut self, asset_fee: Fee, protocol_fee: Fee, block_number: u64) -> Self {
         self.initial_fee = Some((asset_fee, protocol_fee, block_number));
         self
@@ -206,17 +234,31 @@ pub struct OracleProvider;
 impl VolumeProvider<AssetId, Balance> for OracleProvider {
     type Volume = AssetVolume;
 
-    fn asset_volume(asset_id: AssetId) -> Option<Self::Volume> {
+    fn asset_volume(asset_id: AssetId, period: OraclePeriod) -> Option<Self::Volume> {
+        REQUESTED_PERIOD.with(|v| *v.borrow_mut() = Some(period));
         let volume = ORACLE.with(|v| v.borrow().volume(asset_id, BLOCK.with(|v| *v.borrow())));
         Some(volume)
     }
 
-    fn asset_liquidity(asset_id: AssetId) -> Option<Balance> {
+    fn asset_liquidity(asset_id: AssetId, _period: OraclePeriod) -> Option<Balance> {
         let liquidity = ORACLE.with(|v| v.borrow().liquidity(asset_id, BLOCK.with(|v| *v.borrow())));
         Some(liquidity)
     }
 }
 
+pub struct PriceOracleProvider;
+
+impl PriceProvider<AssetId> for PriceOracleProvider {
+    fn asset_price(_asset_id: AssetId, period: OraclePeriod) -> Option<FixedU128> {
+        PRICES.with(|v| {
+            v.borrow()
+                .iter()
+                .find(|(price_period, _)| *price_period == period)
+                .map(|(_, price)| *price)
+        })
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct AssetVolume {
     pub(crate) amount_in: Balance,
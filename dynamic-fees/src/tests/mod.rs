@@ -1,7 +1,11 @@
 mod decay;
+mod events;
+mod fee_params;
 mod fees;
 mod limits;
-mod mock;
+pub(crate) mod mock;
 mod oracle;
 mod oracle_fees;
 mod property;
+mod simulation;
+mod volatility;
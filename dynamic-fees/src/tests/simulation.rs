@@ -0,0 +1,355 @@
+use crate::simulation::{observations_from_csv, simulate, trajectory_to_csv, FeePoint, Observation};
+use crate::tests::mock::*;
+use crate::types::{FeeParams, FeeParamsOverride, VolatilityParams};
+use frame_support::assert_ok;
+use hydradx_traits::OraclePeriod;
+use proptest::prelude::*;
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::FixedU128;
+
+const MAX_VOLUME: Balance = 1_000;
+const MIN_LIQUIDITY: Balance = 1_000;
+const MAX_LIQUIDITY: Balance = 100_000;
+
+fn fee_params(decay: FixedU128, amplification: FixedU128) -> FeeParams<Fee> {
+    FeeParams {
+        min_fee: Fee::from_percent(1),
+        max_fee: Fee::from_percent(40),
+        decay,
+        amplification,
+    }
+}
+
+fn initial_fee() -> impl Strategy<Value = Fee> {
+    (0.02..0.4).prop_map(Fee::from_float)
+}
+
+fn decay() -> impl Strategy<Value = FixedU128> {
+    (1u128..1_000).prop_map(|d| FixedU128::from_rational(d, 100_000))
+}
+
+fn amplification() -> impl Strategy<Value = FixedU128> {
+    (1u128..10_000).prop_map(|a| FixedU128::from_rational(a, 100))
+}
+
+prop_compose! {
+    fn observations()(steps in prop::collection::vec((1u64..20, 0..MAX_VOLUME, 0..MAX_VOLUME, MIN_LIQUIDITY..MAX_LIQUIDITY), 1..50)
+    ) -> Vec<Observation> {
+        let mut block = 0;
+        steps.into_iter().map(|(delta, amount_in, amount_out, liquidity)| {
+            block += delta;
+            Observation { block, amount_in: amount_in * ONE, amount_out: amount_out * ONE, liquidity: liquidity * ONE, prices: None }
+        }).collect()
+    }
+}
+
+prop_compose! {
+    fn observations_without_volume()(deltas in prop::collection::vec(1u64..20, 1..50), liquidity in MIN_LIQUIDITY..MAX_LIQUIDITY
+    ) -> Vec<Observation> {
+        let mut block = 0;
+        deltas.into_iter().map(|delta| {
+            block += delta;
+            Observation { block, amount_in: 0, amount_out: 0, liquidity: liquidity * ONE, prices: None }
+        }).collect()
+    }
+}
+
+#[test]
+fn simulation_should_replay_observations_like_the_pallet() {
+    let params = fee_params(FixedU128::zero(), FixedU128::one());
+    let observations = vec![Observation {
+        block: 1,
+        amount_in: ONE,
+        amount_out: 2 * ONE,
+        liquidity: 50 * ONE,
+        prices: None,
+    }];
+
+    let trajectory = simulate(
+        params,
+        params,
+        None,
+        (Fee::from_percent(2), Fee::zero()),
+        0,
+        &observations,
+    );
+
+    let pallet_fees = ExtBuilder::default()
+        .with_oracle(crate::tests::oracle::SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .with_asset_fee_params(params.min_fee, params.max_fee, params.decay, params.amplification)
+        .with_protocol_fee_params(params.min_fee, params.max_fee, params.decay, params.amplification)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            retrieve_fee_entry(HDX)
+        });
+
+    assert_eq!(
+        trajectory,
+        vec![FeePoint {
+            block: 1,
+            asset_fee: pallet_fees.0,
+            protocol_fee: pallet_fees.1,
+        }]
+    );
+}
+
+#[test]
+fn simulation_should_match_the_pallet_with_volatility_over_multiple_blocks() {
+    let params = fee_params(FixedU128::zero(), FixedU128::one());
+    let (short_price, long_price) = (FixedU128::from_rational(11, 10), FixedU128::one());
+    let observations: Vec<Observation> = (1..=3)
+        .map(|block| Observation {
+            block,
+            amount_in: ONE,
+            amount_out: 2 * ONE,
+            liquidity: 50 * ONE,
+            prices: Some((short_price, long_price)),
+        })
+        .collect();
+
+    let trajectory = simulate(
+        params,
+        params,
+        Some(FixedU128::one()),
+        (Fee::from_percent(2), Fee::zero()),
+        0,
+        &observations,
+    );
+
+    let pallet_trajectory = ExtBuilder::default()
+        .with_oracle(crate::tests::oracle::SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .with_asset_fee_params(params.min_fee, params.max_fee, params.decay, params.amplification)
+        .with_protocol_fee_params(params.min_fee, params.max_fee, params.decay, params.amplification)
+        .with_volatility(
+            VolatilityParams {
+                short_period: OraclePeriod::Short,
+                long_period: OraclePeriod::Day,
+                amplification: FixedU128::one(),
+            },
+            vec![(OraclePeriod::Short, short_price), (OraclePeriod::Day, long_price)],
+        )
+        .build()
+        .execute_with(|| {
+            (1..=3)
+                .map(|block| {
+                    System::set_block_number(block);
+                    let (asset_fee, protocol_fee) = retrieve_fee_entry(HDX);
+                    FeePoint {
+                        block,
+                        asset_fee,
+                        protocol_fee,
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+
+    assert_eq!(trajectory, pallet_trajectory);
+}
+
+#[test]
+fn simulation_should_match_the_pallet_with_asset_specific_fee_params() {
+    let global_params = fee_params(FixedU128::zero(), FixedU128::one());
+    let asset_params = FeeParamsOverride {
+        asset_fee_params: FeeParams {
+            min_fee: Fee::from_float(0.0005),
+            max_fee: Fee::from_percent(1),
+            decay: FixedU128::zero(),
+            amplification: FixedU128::from(2),
+        },
+        protocol_fee_params: FeeParams {
+            min_fee: Fee::zero(),
+            max_fee: Fee::from_float(0.0005),
+            decay: FixedU128::zero(),
+            amplification: FixedU128::one(),
+        },
+    };
+    let observations = vec![Observation {
+        block: 1,
+        amount_in: ONE,
+        amount_out: 2 * ONE,
+        liquidity: 50 * ONE,
+        prices: None,
+    }];
+
+    let trajectory = simulate(
+        asset_params.asset_fee_params,
+        asset_params.protocol_fee_params,
+        None,
+        (Fee::from_float(0.0005), Fee::zero()),
+        0,
+        &observations,
+    );
+
+    let pallet_fees = ExtBuilder::default()
+        .with_oracle(crate::tests::oracle::SingleValueOracle::new(ONE, 2 * ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_float(0.0005), Fee::zero(), 0)
+        .with_asset_fee_params(
+            global_params.min_fee,
+            global_params.max_fee,
+            global_params.decay,
+            global_params.amplification,
+        )
+        .build()
+        .execute_with(|| {
+            assert_ok!(DynamicFees::set_fee_params(
+                RuntimeOrigin::root(),
+                HDX,
+                Some(asset_params)
+            ));
+            System::set_block_number(1);
+            retrieve_fee_entry(HDX)
+        });
+
+    assert_eq!(
+        trajectory,
+        vec![FeePoint {
+            block: 1,
+            asset_fee: pallet_fees.0,
+            protocol_fee: pallet_fees.1,
+        }]
+    );
+}
+
+#[test]
+fn observations_with_prices_should_be_parsed_from_csv() {
+    let csv = "block,amount_in,amount_out,liquidity,short_price,long_price\n1,100,200,5000,1100000000000000000,1000000000000000000\n";
+
+    assert_eq!(
+        observations_from_csv(csv),
+        Ok(vec![Observation {
+            block: 1,
+            amount_in: 100,
+            amount_out: 200,
+            liquidity: 5_000,
+            prices: Some((FixedU128::from_rational(11, 10), FixedU128::one())),
+        }])
+    );
+    assert!(observations_from_csv("block,amount_in,amount_out,liquidity,short_price,long_price\n1,2,3,4\n").is_err());
+}
+
+#[test]
+fn simulation_should_skip_observations_of_already_updated_blocks() {
+    let params = fee_params(FixedU128::zero(), FixedU128::one());
+    let observation = |block| Observation {
+        block,
+        amount_in: ONE,
+        amount_out: 2 * ONE,
+        liquidity: 50 * ONE,
+        prices: None,
+    };
+
+    let trajectory = simulate(
+        params,
+        params,
+        None,
+        (Fee::from_percent(2), Fee::zero()),
+        5,
+        &[observation(5), observation(6), observation(6)],
+    );
+
+    assert_eq!(trajectory.len(), 1);
+    assert_eq!(trajectory[0].block, 6);
+}
+
+#[test]
+fn observations_should_be_parsed_from_csv() {
+    let csv = "block,amount_in,amount_out,liquidity\n1,100,200,5000\n\n3,0,0,5000\n";
+
+    assert_eq!(
+        observations_from_csv(csv),
+        Ok(vec![
+            Observation {
+                block: 1,
+                amount_in: 100,
+                amount_out: 200,
+                liquidity: 5_000,
+                prices: None,
+            },
+            Observation {
+                block: 3,
+                amount_in: 0,
+                amount_out: 0,
+                liquidity: 5_000,
+                prices: None,
+            },
+        ])
+    );
+}
+
+#[test]
+fn observations_from_csv_should_fail_on_invalid_input() {
+    assert!(observations_from_csv("block,liquidity\n1,2\n").is_err());
+    assert!(observations_from_csv("block,amount_in,amount_out,liquidity\n1,2,3\n").is_err());
+    assert!(observations_from_csv("block,amount_in,amount_out,liquidity\n1,2,x,4\n").is_err());
+}
+
+#[test]
+fn trajectory_should_be_formatted_as_csv() {
+    let trajectory = vec![
+        FeePoint {
+            block: 1,
+            asset_fee: Fee::from_percent(4),
+            protocol_fee: Fee::from_percent(1),
+        },
+        FeePoint {
+            block: 2,
+            asset_fee: Fee::from_parts(1),
+            protocol_fee: Fee::from_percent(100),
+        },
+    ];
+
+    assert_eq!(
+        trajectory_to_csv(&trajectory),
+        "block,asset_fee,protocol_fee\n\
+         1,0.040000000000000000,0.010000000000000000\n\
+         2,0.000000000000000001,1.000000000000000000\n"
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+    #[test]
+    fn simulated_fees_should_stay_within_bounds(initial_asset_fee in initial_fee(),
+        initial_protocol_fee in initial_fee(),
+        decay in decay(),
+        amplification in amplification(),
+        observations in observations()) {
+
+        let params = fee_params(decay, amplification);
+
+        let trajectory = simulate(params, params, None, (initial_asset_fee, initial_protocol_fee), 0, &observations);
+
+        for point in trajectory {
+            prop_assert!(params.min_fee <= point.asset_fee && point.asset_fee <= params.max_fee,
+                "Asset fee {:?} out of bounds in block {}", point.asset_fee, point.block);
+            prop_assert!(params.min_fee <= point.protocol_fee && point.protocol_fee <= params.max_fee,
+                "Protocol fee {:?} out of bounds in block {}", point.protocol_fee, point.block);
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+    #[test]
+    fn simulated_fees_should_decay_monotonically_without_volume(initial_asset_fee in initial_fee(),
+        initial_protocol_fee in initial_fee(),
+        decay in decay(),
+        observations in observations_without_volume()) {
+
+        let params = fee_params(decay, FixedU128::one());
+
+        let trajectory = simulate(params, params, None, (initial_asset_fee, initial_protocol_fee), 0, &observations);
+
+        let mut previous = (initial_asset_fee, initial_protocol_fee);
+        for point in trajectory {
+            prop_assert!(point.asset_fee <= previous.0,
+                "Asset fee increased from {:?} to {:?} in block {}", previous.0, point.asset_fee, point.block);
+            prop_assert!(point.protocol_fee <= previous.1,
+                "Protocol fee increased from {:?} to {:?} in block {}", previous.1, point.protocol_fee, point.block);
+            previous = (point.asset_fee, point.protocol_fee);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::tests::mock::*;
+use crate::tests::oracle::SingleValueOracle;
+use crate::types::VolatilityParams;
+use hydradx_traits::OraclePeriod;
+use sp_runtime::traits::{One, Zero};
+use sp_runtime::FixedU128;
+
+fn volatility_params(amplification: FixedU128) -> VolatilityParams {
+    VolatilityParams {
+        short_period: OraclePeriod::Short,
+        long_period: OraclePeriod::Day,
+        amplification,
+    }
+}
+
+fn balanced_volume() -> ExtBuilder {
+    ExtBuilder::default()
+        .with_oracle(SingleValueOracle::new(ONE, ONE, 50 * ONE))
+        .with_initial_fees(Fee::from_percent(2), Fee::zero(), 0)
+        .with_asset_fee_params(
+            Fee::from_percent(1),
+            Fee::from_percent(40),
+            FixedU128::zero(),
+            FixedU128::one(),
+        )
+}
+
+#[test]
+fn asset_fee_should_increase_when_short_and_long_price_diverge() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![
+                (OraclePeriod::Short, FixedU128::from_rational(11, 10)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let fee = retrieve_fee_entry(HDX);
+
+            assert_eq!(fee.0, Fee::from_percent(12));
+        });
+}
+
+#[test]
+fn asset_fee_should_not_compound_volatility_over_blocks() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![
+                (OraclePeriod::Short, FixedU128::from_rational(11, 10)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            for block in 1..=5 {
+                System::set_block_number(block);
+
+                let fee = retrieve_fee_entry(HDX);
+
+                assert_eq!(fee.0, Fee::from_percent(12));
+                assert_eq!(
+                    DynamicFees::current_fees(HDX).map(|entry| entry.asset_fee),
+                    Some(Fee::from_percent(2))
+                );
+            }
+        });
+}
+
+#[test]
+fn asset_fee_should_include_volatility_when_retrieved_again_in_block() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![
+                (OraclePeriod::Short, FixedU128::from_rational(11, 10)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            assert_eq!(retrieve_fee_entry(HDX), retrieve_fee_entry(HDX));
+            assert_eq!(DynamicFees::simulate_fees(HDX, 1).0, Fee::from_percent(12));
+        });
+}
+
+#[test]
+fn asset_fee_should_increase_when_short_price_is_below_long_price() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![
+                (OraclePeriod::Short, FixedU128::from_rational(9, 10)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let fee = retrieve_fee_entry(HDX);
+
+            assert_eq!(fee.0, Fee::from_percent(12));
+        });
+}
+
+#[test]
+fn asset_fee_with_volatility_should_not_exceed_max_fee() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::from(10)),
+            vec![
+                (OraclePeriod::Short, FixedU128::from(2)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let fee = retrieve_fee_entry(HDX);
+
+            assert_eq!(fee.0, Fee::from_percent(40));
+        });
+}
+
+#[test]
+fn asset_fee_should_not_change_when_prices_are_not_available() {
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![(OraclePeriod::Short, FixedU128::from(2))],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let fee = retrieve_fee_entry(HDX);
+
+            assert_eq!(fee.0, Fee::from_percent(2));
+        });
+}
+
+#[test]
+fn protocol_fee_should_not_be_affected_by_volatility() {
+    let protocol_fee_without_volatility = balanced_volume().build().execute_with(|| {
+        System::set_block_number(1);
+        retrieve_fee_entry(HDX).1
+    });
+
+    balanced_volume()
+        .with_volatility(
+            volatility_params(FixedU128::one()),
+            vec![
+                (OraclePeriod::Short, FixedU128::from(2)),
+                (OraclePeriod::Day, FixedU128::one()),
+            ],
+        )
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            let fee = retrieve_fee_entry(HDX);
+
+            assert_eq!(fee.1, protocol_fee_without_volatility);
+        });
+}
+
+#[test]
+fn configured_oracle_period_should_be_used_for_volume() {
+    balanced_volume()
+        .with_volume_period(OraclePeriod::Day)
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+
+            retrieve_fee_entry(HDX);
+
+            assert_eq!(REQUESTED_PERIOD.with(|v| *v.borrow()), Some(OraclePeriod::Day));
+        });
+}
@@ -1,3 +1,6 @@
+use hydradx_traits::OraclePeriod;
+use sp_runtime::FixedU128;
+
 pub trait Volume<Balance> {
     fn amount_in(&self) -> Balance;
     fn amount_out(&self) -> Balance;
@@ -6,7 +9,18 @@ pub trait Volume<Balance> {
 pub trait VolumeProvider<AssetId, Balance> {
     type Volume: Volume<Balance>;
 
-    fn asset_volume(asset_id: AssetId) -> Option<Self::Volume>;
+    fn asset_volume(asset_id: AssetId, period: OraclePeriod) -> Option<Self::Volume>;
+
+    fn asset_liquidity(asset_id: AssetId, period: OraclePeriod) -> Option<Balance>;
+}
+
+pub trait PriceProvider<AssetId> {
+    /// Returns the price of an asset averaged over `period`.
+    fn asset_price(asset_id: AssetId, period: OraclePeriod) -> Option<FixedU128>;
+}
 
-    fn asset_liquidity(asset_id: AssetId) -> Option<Balance>;
+impl<AssetId> PriceProvider<AssetId> for () {
+    fn asset_price(_asset_id: AssetId, _period: OraclePeriod) -> Option<FixedU128> {
+        None
+    }
 }
@@ -1,13 +1,15 @@
 use frame_support::pallet_prelude::*;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
+use sp_runtime::traits::Zero;
 use sp_runtime::FixedU128;
 
 use hydra_dx_math::dynamic_fees::types::FeeParams as MathFeeParams;
 
+use hydradx_traits::OraclePeriod;
 use scale_info::TypeInfo;
 
-#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct FeeParams<Fee> {
     pub min_fee: Fee,
@@ -24,6 +26,33 @@ pub struct FeeEntry<Fee, Block> {
     pub timestamp: Block,
 }
 
+impl<Fee: PartialOrd> FeeParams<Fee> {
+    /// Returns `true` if `min_fee <= max_fee` and amplification is not zero.
+    pub fn is_valid(&self) -> bool {
+        self.min_fee <= self.max_fee && !self.amplification.is_zero()
+    }
+}
+
+/// Parameters of the volatility component of the asset fee.
+///
+/// The volatility is the relative divergence `|short - long| / long` of the asset price averaged over `short_period`
+/// and `long_period`. It is multiplied by `amplification` and added to the asset fee.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VolatilityParams {
+    pub short_period: OraclePeriod,
+    pub long_period: OraclePeriod,
+    pub amplification: FixedU128,
+}
+
+/// Asset specific fee parameters overriding the global `AssetFeeParameters` and `ProtocolFeeParameters`.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FeeParamsOverride<Fee> {
+    pub asset_fee_params: FeeParams<Fee>,
+    pub protocol_fee_params: FeeParams<Fee>,
+}
+
 impl<Fee> From<FeeParams<Fee>> for MathFeeParams<Fee> {
     fn from(value: FeeParams<Fee>) -> Self {
         MathFeeParams {
@@ -0,0 +1,58 @@
+// This file is part of pallet-dynamic-fees.
+
+// Copyright (C) 2020-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_dynamic_fees
+//!
+//! NOTE: These are placeholder values that have not been benchmarked yet. They are meant to be
+//! replaced by running the benchmarks in `benchmarking.rs`, e.g.:
+//!
+//! ```text
+//! target/release/basilisk benchmark pallet --chain=dev --steps=5 --repeat=20 --execution=wasm
+//! --wasm-execution=compiled --heap-pages=4096
+//! --template=.maintain/pallet-weight-template-no-back.hbs --pallet=pallet_dynamic_fees
+//! --output=dynamic_fees.rs --extrinsic=*
+//! ```
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_dynamic_fees.
+pub trait WeightInfo {
+    fn set_fee_params() -> Weight;
+}
+
+pub struct BasiliskWeight<T>(PhantomData<T>);
+
+impl<T: frame_system::Config> WeightInfo for BasiliskWeight<T> {
+    fn set_fee_params() -> Weight {
+        Weight::from_ref_time(15_104_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn set_fee_params() -> Weight {
+        Weight::from_ref_time(15_104_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+}
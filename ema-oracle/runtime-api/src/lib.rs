@@ -0,0 +1,62 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the EMA oracle pallet.
+//!
+//! Exposes the oracle entries as they are used on chain, i.e. updated to the parent block, so
+//! off-chain consumers do not have to decode the storage and fast-forward the values themselves.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_ema_oracle::{AssetId, OracleEntry, OraclePeriod, Source, SourceContribution};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait EmaOracleApi<BlockNumber>
+    where
+        BlockNumber: Codec,
+    {
+        /// Return the oracle entry of `source` for `asset_a/asset_b` and `period` together with
+        /// the block number of its initialization.
+        ///
+        /// The entry is updated to the parent block and adjusted to the given order of assets.
+        fn get_entry(
+            source: Source,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            period: OraclePeriod,
+        ) -> Option<(OracleEntry<BlockNumber>, BlockNumber)>;
+
+        /// Return the oracle entries of `source` for `asset_a/asset_b` for all periods supported
+        /// by the runtime. See `get_entry`.
+        fn get_entries(
+            source: Source,
+            asset_a: AssetId,
+            asset_b: AssetId,
+        ) -> Vec<(OraclePeriod, OracleEntry<BlockNumber>, BlockNumber)>;
+
+        /// Return the price of `asset_a/asset_b` for `period` of each of `sources` as it
+        /// contributes to a price aggregated across sources. Sources without an oracle are skipped.
+        fn get_source_contributions(
+            sources: Vec<Source>,
+            asset_a: AssetId,
+            asset_b: AssetId,
+            period: OraclePeriod,
+        ) -> Vec<SourceContribution<BlockNumber>>;
+    }
+}
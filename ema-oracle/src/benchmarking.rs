@@ -24,6 +24,7 @@ pub const DOT: AssetId = 2_000;
 
 use frame_benchmarking::benchmarks;
 use frame_support::{assert_ok, traits::Hooks};
+use sp_arithmetic::Permill;
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
@@ -280,5 +281,85 @@ benchmarks! {
         }));
     }
 
+    add_oracle {
+        let successful_origin = T::AuthorityOrigin::try_successful_origin().unwrap();
+    }: _<T::RuntimeOrigin>(successful_origin, SOURCE, (HDX, DOT))
+    verify {
+        assert!(WhitelistedAssets::<T>::contains_key((SOURCE, ordered_pair(HDX, DOT))));
+    }
+
+    remove_oracle {
+        let p in 1 .. MAX_ORACLES_PER_PAIR;
+
+        assert_ok!(EmaOracle::<T>::add_oracle(T::AuthorityOrigin::try_successful_origin().unwrap(), SOURCE, (HDX, DOT)));
+
+        let block_num: T::BlockNumber = 5u32.into();
+        frame_system::Pallet::<T>::set_block_number(block_num);
+        EmaOracle::<T>::on_initialize(block_num);
+        let (amount_in, amount_out) = (1_000_000_000_000, 2_000_000_000_000);
+        let (liquidity_asset_in, liquidity_asset_out) = (1_000_000_000_000_000, 2_000_000_000_000_000);
+        assert_ok!(OnActivityHandler::<T>::on_trade(SOURCE, HDX, DOT, amount_in, amount_out, liquidity_asset_in, liquidity_asset_out));
+        EmaOracle::<T>::on_finalize(block_num);
+        let (entry, initialized) = Oracles::<T>::get((SOURCE, ordered_pair(HDX, DOT), LastBlock)).unwrap();
+        // fill up the remaining entries of the pair to simulate the maximum number of tracked periods
+        let stored = Oracles::<T>::iter_prefix((SOURCE, ordered_pair(HDX, DOT))).count() as u32;
+        for i in stored .. p {
+            Oracles::<T>::insert((SOURCE, ordered_pair(HDX, DOT), Custom(1_000_000 + i)), (entry.clone(), initialized));
+        }
+
+        let successful_origin = T::AuthorityOrigin::try_successful_origin().unwrap();
+    }: _<T::RuntimeOrigin>(successful_origin, SOURCE, (HDX, DOT))
+    verify {
+        assert!(!WhitelistedAssets::<T>::contains_key((SOURCE, ordered_pair(HDX, DOT))));
+        assert_eq!(Oracles::<T>::iter_prefix((SOURCE, ordered_pair(HDX, DOT))).count(), 0);
+    }
+
+    set_price_change_limit {
+        let limit = PriceChangeLimit {
+            max_change: Permill::from_percent(10),
+            action: PriceLimitAction::Clamp,
+        };
+        let successful_origin = T::AuthorityOrigin::try_successful_origin().unwrap();
+    }: _<T::RuntimeOrigin>(successful_origin, SOURCE, Some(limit))
+    verify {
+        assert_eq!(PriceChangeLimits::<T>::get(SOURCE), Some(limit));
+    }
+
+    set_custom_periods {
+        // removing a period starts the removal of stale oracles
+        let previous: BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>> = (1_000_001..=1_000_000 + MAX_CUSTOM_PERIODS).collect::<Vec<_>>().try_into().unwrap();
+        CustomPeriods::<T>::put(previous);
+        let periods: BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>> = (1..=MAX_CUSTOM_PERIODS).collect::<Vec<_>>().try_into().unwrap();
+        let successful_origin = T::AuthorityOrigin::try_successful_origin().unwrap();
+    }: _<T::RuntimeOrigin>(successful_origin, periods.clone())
+    verify {
+        assert_eq!(CustomPeriods::<T>::get(), periods);
+        assert!(StaleOraclesCursor::<T>::exists());
+    }
+
+    remove_stale_oracles {
+        let n in 1 .. T::MaxUniqueEntries::get();
+
+        let block_num: T::BlockNumber = 5u32.into();
+        let (liquidity_asset_in, liquidity_asset_out) = (1_000_000_000_000_000, 2_000_000_000_000_000);
+        let entry = OracleEntry {
+            price: Price::from((liquidity_asset_in, liquidity_asset_out)),
+            volume: Volume::default(),
+            liquidity: Liquidity::new(liquidity_asset_in, liquidity_asset_out),
+            timestamp: block_num,
+        };
+        // oracles of a period that is not supported
+        for i in 0 .. n {
+            let asset_a = i * 1_000;
+            let asset_b = asset_a + 500;
+            Oracles::<T>::insert((SOURCE, ordered_pair(asset_a, asset_b), Custom(1_000_000)), (entry.clone(), block_num));
+        }
+        StaleOraclesCursor::<T>::put(BoundedVec::default());
+    }: { EmaOracle::<T>::remove_stale_oracles(Weight::MAX); }
+    verify {
+        assert_eq!(Oracles::<T>::iter_keys().count(), 0);
+        assert!(!StaleOraclesCursor::<T>::exists());
+    }
+
     impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::Test);
 }
@@ -28,13 +28,42 @@
 //! Data is ingested by plugging the provided `OnActivityHandler` into callbacks provided by other
 //! pallets (e.g. xyk pallet).
 //!
+//! Only data for whitelisted combinations of source and asset pair is tracked. An entry is
+//! whitelisted either by the `OracleWhitelist` filter configured in the runtime or by adding it to
+//! the `WhitelistedAssets` storage via `add_oracle`. Data for all other entries is ignored.
+//!
 //! It is meant to be used by other pallets via the `AggregatedOracle` and `AggregatedPriceOracle`
 //! traits.
 //!
+//! To limit the impact of single block price manipulation a `PriceChangeLimit` can be configured per
+//! source via `set_price_change_limit`. Incoming prices deviating more than allowed from the
+//! previous `LastBlock` price are then either clamped or the update is ignored. After
+//! `MaxIgnoredUpdates` consecutive ignored updates the next one is accepted.
+//!
+//! In addition to the EMA oracles the pallet keeps a bounded buffer of price observations per
+//! source and asset pair which is used to provide the time weighted average price (TWAP) over the
+//! configured `TwapWindow`. It is exposed as `AggregatedPriceOracle` by `TwapOracle`. No TWAP is
+//! returned if the observations do not cover the whole window.
+//!
+//! Consumers that require settled and recent prices can wrap the pallet with `FreshPriceOracle`
+//! from `hydradx-traits`. It rejects prices of oracles younger than a minimum age (`NotReady`) and
+//! of oracles that did not receive data for longer than a maximum staleness (`Stale`).
+//!
+//! When the same pair is traded via several sources, `CrossSourcePriceOracle` combines the
+//! oracles of the configured sources into one price, either as liquidity weighted average or as
+//! median. The contribution of each source can be inspected via `get_source_contributions`, which
+//! is also exposed by the runtime API.
+//!
+//! Prices for asset pairs without a direct oracle can be obtained via `DerivedPriceOracle` which
+//! chains the oracles along a route given by an `OracleRouteProvider`, e.g. `A/LRNA * LRNA/B`.
+//!
 //! When integrating with this pallet take care to use the `on_trade_weight`,
 //! `on_liquidity_changed_weight` and `get_entry_weight` into account when calculating the weight
 //! for your extrinsics (that either feed data into or take data from this pallet).
 //!
+//! Off-chain consumers can query up-to-date oracle entries via the `EmaOracleApi` runtime API
+//! defined in the `pallet-ema-oracle-runtime-api` crate.
+//!
 //! ### Concepts
 //!
 //! - *EMA*: Averaging via exponential decay with a smoothing factor; meaning each new value to
@@ -43,6 +72,10 @@
 //!   Implicitly determines the oracle period.
 //! - *Period*: The window over which an oracle is averaged. Certain smoothing factors correspond to
 //!   an oracle period. E.g. ten minutes oracle period ≈ 0.0198
+//!   Besides the predefined periods the runtime and governance (via `set_custom_periods`) can
+//!   configure `Custom` periods given as a number of blocks (e.g. a 30 day oracle) with the
+//!   smoothing factor derived from the period length. Oracles of periods that are no longer
+//!   supported are not returned anymore and are removed in `on_idle`.
 //! - *Source*: The source of the data. E.g. xyk pallet.
 //!
 //! ### Implementation
@@ -68,13 +101,14 @@
 
 use frame_support::pallet_prelude::*;
 use frame_support::sp_runtime::traits::{BlockNumberProvider, One, Zero};
+use frame_support::traits::Contains;
 use hydradx_traits::{
-    AggregatedEntry, AggregatedOracle, AggregatedPriceOracle, Liquidity, OnCreatePoolHandler,
-    OnLiquidityChangedHandler, OnTradeHandler,
+    AggregatedEntry, AggregatedOracle, AggregatedPriceInfoOracle, AggregatedPriceOracle, Liquidity,
+    OnCreatePoolHandler, OnLiquidityChangedHandler, OnTradeHandler,
     OraclePeriod::{self, *},
-    Volume,
+    OraclePriceInfo, OracleRouteHop, OracleRouteProvider, Volume,
 };
-use sp_arithmetic::traits::Saturating;
+use sp_arithmetic::traits::{SaturatedConversion, Saturating};
 use sp_std::marker::PhantomData;
 use sp_std::prelude::*;
 
@@ -94,10 +128,17 @@ mod benchmarking;
 /// `trades_estimation.py` and rounding up from 212 to 300. Not necessarily representative for all
 /// chains, configure `MaxUniqueEntries` according to your chain.
 pub const MAX_UNIQUE_ENTRIES: u32 = 300;
+/// The maximum number of `Custom` periods that can be supported in addition to the predefined ones.
+pub const MAX_CUSTOM_PERIODS: u32 = 5;
 /// The maximum number of periods that could have corresponding oracles.
-pub const MAX_PERIODS: u32 = OraclePeriod::all_periods().len() as u32;
+pub const MAX_PERIODS: u32 = OraclePeriod::all_periods().len() as u32 + MAX_CUSTOM_PERIODS;
+/// The maximum number of oracle entries that can be stored for one source and asset pair, i.e. one
+/// for each of the `SupportedPeriods` and the custom periods set by governance.
+pub const MAX_ORACLES_PER_PAIR: u32 = MAX_PERIODS + MAX_CUSTOM_PERIODS;
 
 const LOG_TARGET: &str = "runtime::ema-oracle";
+/// The maximum length of a raw `Oracles` storage key.
+const MAX_ORACLE_KEY_LEN: u32 = 128;
 
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
@@ -107,7 +148,7 @@ pub use pallet::*;
 pub mod pallet {
     use super::*;
     use frame_support::BoundedBTreeMap;
-    use frame_system::pallet_prelude::BlockNumberFor;
+    use frame_system::pallet_prelude::{BlockNumberFor, OriginFor};
 
     #[pallet::pallet]
     pub struct Pallet<T>(_);
@@ -119,25 +160,88 @@ pub mod pallet {
         /// Weight information for the extrinsics.
         type WeightInfo: WeightInfo;
 
+        /// Origin that can add and remove oracles from the whitelist.
+        type AuthorityOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
         /// Provider for the current block number.
         type BlockNumberProvider: BlockNumberProvider<BlockNumber = Self::BlockNumber>;
 
         /// The periods supported by the pallet. I.e. which oracles to track.
+        ///
+        /// Must include `LastBlock` as it is used to update the other periods. Governance can track
+        /// additional `Custom` periods via `set_custom_periods`.
         type SupportedPeriods: Get<BoundedVec<OraclePeriod, ConstU32<MAX_PERIODS>>>;
 
+        /// Filter determining which oracles are tracked without being added to `WhitelistedAssets`.
+        type OracleWhitelist: Contains<(Source, AssetId, AssetId)>;
+
         /// Maximum number of unique oracle entries expected in one block.
         #[pallet::constant]
         type MaxUniqueEntries: Get<u32>;
+
+        /// Number of blocks the time weighted average price (TWAP) is calculated over.
+        #[pallet::constant]
+        type TwapWindow: Get<u32>;
+
+        /// Maximum number of price observations kept per oracle for the TWAP calculation.
+        ///
+        /// Must be at least `TwapWindow` so that pairs traded every block still cover the window.
+        #[pallet::constant]
+        type MaxTwapObservations: Get<u32>;
+
+        /// Number of consecutive updates exceeding an `Ignore` price change limit that are ignored.
+        /// The next exceeding update is accepted so the oracle can follow lasting price moves.
+        #[pallet::constant]
+        type MaxIgnoredUpdates: Get<u32>;
     }
 
     #[pallet::error]
     pub enum Error<T> {
         TooManyUniqueEntries,
         OnTradeValueZero,
+        /// The oracle is not present in the whitelist.
+        OracleNotFound,
+        /// Custom periods should span at least one block and be unique.
+        InvalidCustomPeriods,
     }
 
     #[pallet::event]
-    pub enum Event<T: Config> {}
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// Oracle was added to the whitelist.
+        AddedToWhitelist { source: Source, assets: (AssetId, AssetId) },
+        /// Oracle was removed from the whitelist.
+        RemovedFromWhitelist { source: Source, assets: (AssetId, AssetId) },
+        /// Price change limit of a source was set or removed.
+        PriceChangeLimitSet {
+            source: Source,
+            limit: Option<PriceChangeLimit>,
+        },
+        /// Incoming price exceeded the price change limit and was clamped.
+        PriceClamped {
+            source: Source,
+            assets: (AssetId, AssetId),
+            price: Price,
+            clamped_price: Price,
+        },
+        /// Incoming price exceeded the price change limit and the update was ignored.
+        PriceUpdateIgnored {
+            source: Source,
+            assets: (AssetId, AssetId),
+            price: Price,
+        },
+        /// Incoming price exceeded the price change limit but was accepted because the previous
+        /// `MaxIgnoredUpdates` updates were ignored already.
+        PriceUpdateAccepted {
+            source: Source,
+            assets: (AssetId, AssetId),
+            price: Price,
+        },
+        /// Custom periods tracked in addition to `SupportedPeriods` were set.
+        CustomPeriodsSet {
+            periods: BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>>,
+        },
+    }
 
     /// Accumulator for oracle data in current block that will be recorded at the end of the block.
     #[pallet::storage]
@@ -164,6 +268,47 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Oracles that are tracked in addition to the ones allowed by `OracleWhitelist`, keyed by data
+    /// source and ordered asset pair.
+    #[pallet::storage]
+    pub type WhitelistedAssets<T: Config> = StorageMap<_, Twox64Concat, (Source, (AssetId, AssetId)), (), OptionQuery>;
+
+    /// Price observations used for the time weighted average price, keyed by data source and asset
+    /// pair. Sorted by block number with the most recent observation last.
+    #[pallet::storage]
+    #[pallet::getter(fn twap_observations)]
+    pub type TwapObservations<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        Source,
+        Twox64Concat,
+        (AssetId, AssetId),
+        BoundedVec<(T::BlockNumber, Price), T::MaxTwapObservations>,
+        ValueQuery,
+    >;
+
+    /// Limit on how much incoming prices may deviate from the previous `LastBlock` price per source.
+    #[pallet::storage]
+    #[pallet::getter(fn price_change_limit)]
+    pub type PriceChangeLimits<T: Config> = StorageMap<_, Twox64Concat, Source, PriceChangeLimit, OptionQuery>;
+
+    /// Number of consecutive updates of an oracle that were ignored because of the price change
+    /// limit of its source.
+    #[pallet::storage]
+    #[pallet::getter(fn ignored_updates)]
+    pub type IgnoredUpdates<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Source, Twox64Concat, (AssetId, AssetId), u32, ValueQuery>;
+
+    /// Lengths in blocks of the `Custom` periods tracked in addition to `SupportedPeriods`.
+    #[pallet::storage]
+    #[pallet::getter(fn custom_periods)]
+    pub type CustomPeriods<T: Config> = StorageValue<_, BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>>, ValueQuery>;
+
+    /// Raw key of the last `Oracles` entry checked while removing the oracles of periods that are no
+    /// longer supported. Present while the removal is pending, empty if no entry was checked yet.
+    #[pallet::storage]
+    pub type StaleOraclesCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<MAX_ORACLE_KEY_LEN>>, OptionQuery>;
+
     #[pallet::genesis_config]
     #[derive(Default)]
     pub struct GenesisConfig {
@@ -188,9 +333,12 @@ pub mod pallet {
                     }
                 };
 
-                for period in T::SupportedPeriods::get() {
+                WhitelistedAssets::<T>::insert((source, ordered_pair(asset_a, asset_b)), ());
+
+                for period in Pallet::<T>::supported_periods() {
                     Pallet::<T>::update_oracle(source, ordered_pair(asset_a, asset_b), period, entry.clone());
                 }
+                Pallet::<T>::record_twap_observation(source, ordered_pair(asset_a, asset_b), &entry);
             }
         }
     }
@@ -198,7 +346,8 @@ pub mod pallet {
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            T::WeightInfo::on_finalize_no_entry()
+            // + custom periods read
+            T::WeightInfo::on_finalize_no_entry().saturating_add(T::DbWeight::get().reads(1))
         }
 
         fn on_finalize(_n: BlockNumberFor<T>) {
@@ -206,16 +355,145 @@ pub mod pallet {
             Self::update_oracles_from_accumulator();
         }
 
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::remove_stale_oracles(remaining_weight)
+        }
+
         fn integrity_test() {
             assert!(
                 T::MaxUniqueEntries::get() > 0,
                 "At least one trade should be possible per block."
             );
+            assert!(
+                T::SupportedPeriods::get().contains(&LastBlock),
+                "The `LastBlock` oracle is required to update the other oracles."
+            );
+            assert!(
+                !T::SupportedPeriods::get().contains(&Custom(0)),
+                "Custom periods should span at least one block."
+            );
+            assert!(
+                T::MaxTwapObservations::get() >= T::TwapWindow::get(),
+                "The TWAP observations should be able to cover the whole TWAP window."
+            );
         }
     }
 
     #[pallet::call]
-    impl<T: Config> Pallet<T> {}
+    impl<T: Config> Pallet<T> {
+        /// Add an oracle to the whitelist so its data gets tracked.
+        ///
+        /// Parameters:
+        /// - `origin`: Must be `T::AuthorityOrigin`
+        /// - `source`: Source of the oracle data
+        /// - `assets`: Asset pair of the oracle (order does not matter)
+        ///
+        /// Emits `AddedToWhitelist` event if successful.
+        #[pallet::call_index(0)]
+        #[pallet::weight(<T as Config>::WeightInfo::add_oracle())]
+        pub fn add_oracle(origin: OriginFor<T>, source: Source, assets: (AssetId, AssetId)) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            let assets = ordered_pair(assets.0, assets.1);
+
+            WhitelistedAssets::<T>::insert((source, assets), ());
+
+            Self::deposit_event(Event::AddedToWhitelist { source, assets });
+            Ok(())
+        }
+
+        /// Remove an oracle from the whitelist and clear its stored data.
+        ///
+        /// Parameters:
+        /// - `origin`: Must be `T::AuthorityOrigin`
+        /// - `source`: Source of the oracle data
+        /// - `assets`: Asset pair of the oracle (order does not matter)
+        ///
+        /// Emits `RemovedFromWhitelist` event if successful.
+        #[pallet::call_index(1)]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_oracle(MAX_ORACLES_PER_PAIR))]
+        pub fn remove_oracle(origin: OriginFor<T>, source: Source, assets: (AssetId, AssetId)) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            let assets = ordered_pair(assets.0, assets.1);
+
+            ensure!(
+                WhitelistedAssets::<T>::contains_key((source, assets)),
+                Error::<T>::OracleNotFound
+            );
+            WhitelistedAssets::<T>::remove((source, assets));
+
+            let _ = Oracles::<T>::clear_prefix((source, assets), MAX_ORACLES_PER_PAIR, None);
+            Accumulator::<T>::mutate(|accumulator| accumulator.remove(&(source, assets)));
+            TwapObservations::<T>::remove(source, assets);
+            IgnoredUpdates::<T>::remove(source, assets);
+
+            Self::deposit_event(Event::RemovedFromWhitelist { source, assets });
+            Ok(())
+        }
+
+        /// Set or remove the limit on how much incoming prices of `source` may deviate from the
+        /// previous `LastBlock` price.
+        ///
+        /// Parameters:
+        /// - `origin`: Must be `T::AuthorityOrigin`
+        /// - `source`: Source of the oracle data
+        /// - `limit`: New limit or `None` to remove the limit
+        ///
+        /// Emits `PriceChangeLimitSet` event if successful.
+        #[pallet::call_index(2)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_price_change_limit())]
+        pub fn set_price_change_limit(
+            origin: OriginFor<T>,
+            source: Source,
+            limit: Option<PriceChangeLimit>,
+        ) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            PriceChangeLimits::<T>::set(source, limit);
+
+            Self::deposit_event(Event::PriceChangeLimitSet { source, limit });
+            Ok(())
+        }
+
+        /// Set the `Custom` periods tracked in addition to `SupportedPeriods`.
+        ///
+        /// Oracles of newly added periods are initialized with the next incoming data. Oracle
+        /// entries of removed periods are no longer updated or returned and are removed in
+        /// `on_idle`.
+        ///
+        /// Parameters:
+        /// - `origin`: Must be `T::AuthorityOrigin`
+        /// - `periods`: Lengths of the custom periods in blocks, replacing the previous ones
+        ///
+        /// Emits `CustomPeriodsSet` event if successful.
+        #[pallet::call_index(3)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_custom_periods())]
+        pub fn set_custom_periods(
+            origin: OriginFor<T>,
+            periods: BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>>,
+        ) -> DispatchResult {
+            T::AuthorityOrigin::ensure_origin(origin)?;
+
+            ensure!(!periods.contains(&0), Error::<T>::InvalidCustomPeriods);
+            for (i, period) in periods.iter().enumerate() {
+                ensure!(!periods[..i].contains(period), Error::<T>::InvalidCustomPeriods);
+            }
+
+            let supported = T::SupportedPeriods::get();
+            let removed_period = Self::custom_periods()
+                .iter()
+                .any(|period| !periods.contains(period) && !supported.contains(&Custom(*period)));
+            CustomPeriods::<T>::put(periods.clone());
+            if removed_period {
+                // (re)start the removal of stale oracles from the first entry
+                StaleOraclesCursor::<T>::put(BoundedVec::default());
+            }
+
+            Self::deposit_event(Event::CustomPeriodsSet { periods });
+            Ok(())
+        }
+    }
 }
 
 impl<T: Config> Pallet<T> {
@@ -226,6 +504,10 @@ impl<T: Config> Pallet<T> {
         assets: (AssetId, AssetId),
         oracle_entry: OracleEntry<T::BlockNumber>,
     ) -> Result<(), ()> {
+        if !Self::is_tracked(src, assets) {
+            // data for oracles that are not whitelisted is ignored
+            return Ok(());
+        }
         Accumulator::<T>::mutate(|accumulator| {
             if let Some(entry) = accumulator.get_mut(&(src, assets)) {
                 entry.accumulate_volume_and_update_from(&oracle_entry);
@@ -265,6 +547,11 @@ impl<T: Config> Pallet<T> {
             .map_err(|_| (weight, Error::<T>::TooManyUniqueEntries.into()))
     }
 
+    /// Return whether the oracle for the given `src` and `assets` is whitelisted.
+    pub fn is_tracked(src: Source, assets: (AssetId, AssetId)) -> bool {
+        T::OracleWhitelist::contains(&(src, assets.0, assets.1)) || WhitelistedAssets::<T>::contains_key((src, assets))
+    }
+
     /// Return the current value of the `LastBlock` oracle for the given `source` and `assets`.
     pub(crate) fn last_block_oracle(
         source: Source,
@@ -281,16 +568,208 @@ impl<T: Config> Pallet<T> {
         })
     }
 
+    /// Return the periods tracked by the pallet, i.e. `SupportedPeriods` followed by the custom
+    /// periods set by governance that are not part of `SupportedPeriods` already.
+    pub fn supported_periods() -> Vec<OraclePeriod> {
+        let mut periods = T::SupportedPeriods::get().into_inner();
+        for period in Self::custom_periods().into_iter().map(Custom) {
+            if !periods.contains(&period) {
+                periods.push(period);
+            }
+        }
+        periods
+    }
+
+    /// Return whether oracles of `period` are tracked by the pallet, see [`Self::supported_periods`].
+    pub fn is_supported_period(period: OraclePeriod) -> bool {
+        T::SupportedPeriods::get().contains(&period)
+            || matches!(period, Custom(blocks) if Self::custom_periods().contains(&blocks))
+    }
+
+    /// Remove the oracle entries of periods that are no longer supported, continuing after the
+    /// entry checked last. Checks as many entries as `remaining_weight` allows.
+    pub(crate) fn remove_stale_oracles(remaining_weight: Weight) -> Weight {
+        // reading the cursor and the custom periods and writing the cursor
+        let base_weight = T::WeightInfo::remove_stale_oracles(0);
+        // checking and removing an oracle entry
+        let entry_weight = T::WeightInfo::remove_stale_oracles(1).saturating_sub(base_weight);
+
+        // Entries are never checked for free, otherwise the number of entries would not be bounded by the weight.
+        if entry_weight.ref_time().is_zero() || remaining_weight.any_lt(base_weight.saturating_add(entry_weight)) {
+            return Weight::zero();
+        }
+
+        let cursor = match StaleOraclesCursor::<T>::get() {
+            Some(cursor) => cursor,
+            None => return T::DbWeight::get().reads(1),
+        };
+
+        let max_entries = remaining_weight.saturating_sub(base_weight).ref_time() / entry_weight.ref_time();
+
+        let periods = Self::supported_periods();
+        let mut keys = if cursor.is_empty() {
+            Oracles::<T>::iter_keys()
+        } else {
+            Oracles::<T>::iter_keys_from(cursor.into_inner())
+        };
+        let mut checked: u64 = 0;
+        let mut stale = Vec::new();
+        while checked < max_entries {
+            match keys.next() {
+                Some((src, assets, period)) => {
+                    checked = checked.saturating_add(1);
+                    if !periods.contains(&period) {
+                        stale.push((src, assets, period));
+                    }
+                }
+                None => break,
+            }
+        }
+        let finished = checked < max_entries;
+        let last_key = keys.last_raw_key().to_vec();
+
+        for key in stale {
+            Oracles::<T>::remove(key);
+        }
+
+        if finished {
+            StaleOraclesCursor::<T>::kill();
+        } else {
+            match BoundedVec::try_from(last_key) {
+                Ok(cursor) => StaleOraclesCursor::<T>::put(cursor),
+                Err(_) => {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "Oracle storage key is too long to continue removing stale oracles."
+                    );
+                    StaleOraclesCursor::<T>::kill();
+                }
+            }
+        }
+
+        T::WeightInfo::remove_stale_oracles(checked.saturated_into())
+    }
+
     /// Update oracles based on data accumulated during the block.
     fn update_oracles_from_accumulator() {
-        for ((src, assets), oracle_entry) in Accumulator::<T>::take().into_iter() {
+        let periods = Self::supported_periods();
+        for ((src, assets), mut oracle_entry) in Accumulator::<T>::take().into_iter() {
+            if !Self::apply_price_change_limit(src, assets, &mut oracle_entry) {
+                continue;
+            }
             // First we update the non-immediate oracles with the value of the `LastBlock` oracle.
-            for period in T::SupportedPeriods::get().into_iter().filter(|p| *p != LastBlock) {
-                Self::update_oracle(src, assets, period, oracle_entry.clone());
+            for period in periods.iter().filter(|p| **p != LastBlock) {
+                Self::update_oracle(src, assets, *period, oracle_entry.clone());
             }
             // As we use (the old value of) the `LastBlock` entry to update the other oracles it
             // gets updated last.
             Self::update_oracle(src, assets, LastBlock, oracle_entry.clone());
+            Self::record_twap_observation(src, assets, &oracle_entry);
+        }
+    }
+
+    /// Record the price of `oracle_entry` as TWAP observation.
+    ///
+    /// Observations that are no longer needed to cover the TWAP window are pruned. If the buffer is
+    /// still full the oldest observation is dropped.
+    fn record_twap_observation(src: Source, assets: (AssetId, AssetId), oracle_entry: &OracleEntry<T::BlockNumber>) {
+        let max_observations = T::MaxTwapObservations::get() as usize;
+        if max_observations.is_zero() {
+            return;
+        }
+        let window_start = oracle_entry
+            .timestamp
+            .saturating_add(One::one())
+            .saturating_sub(T::TwapWindow::get().into());
+        TwapObservations::<T>::mutate(src, assets, |observations| {
+            // the observation before the window start is still needed as long as its successor is
+            // inside the window
+            while observations.len() > 1 && observations[1].0 <= window_start {
+                observations.remove(0);
+            }
+            // only keep one observation per block
+            if observations.last().map(|(block, _)| *block) == Some(oracle_entry.timestamp) {
+                observations.remove(observations.len() - 1);
+            } else if observations.len() >= max_observations {
+                observations.remove(0);
+            }
+            // cannot fail as we made room above
+            let _ = observations.try_push((oracle_entry.timestamp, oracle_entry.price));
+        });
+    }
+
+    /// Return the time weighted average price for the given source and assets over the
+    /// `TwapWindow` until the parent block together with the age of the oldest observation used.
+    ///
+    /// Returns `None` if the observations do not cover the whole window.
+    fn get_twap_for_pair(src: Source, assets: (AssetId, AssetId)) -> Option<(Price, T::BlockNumber)> {
+        let parent = T::BlockNumberProvider::current_block_number().saturating_sub(One::one());
+        let observations = Self::twap_observations(src, assets);
+        let (oldest, _) = observations.first()?;
+        let age = parent.saturating_sub(*oldest);
+        let observations: Vec<(u64, Price)> = observations
+            .iter()
+            .map(|(block, price)| ((*block).saturated_into(), *price))
+            .collect();
+        calculate_twap(&observations, parent.saturated_into(), u64::from(T::TwapWindow::get()))
+            .map(|price| (price, age))
+    }
+
+    /// Check the price of `oracle_entry` against the price change limit of `src` and clamp it if
+    /// configured to do so.
+    ///
+    /// Returns `false` if the update should be ignored. Once `MaxIgnoredUpdates` consecutive
+    /// updates were ignored, the next one is accepted.
+    fn apply_price_change_limit(
+        src: Source,
+        assets: (AssetId, AssetId),
+        oracle_entry: &mut OracleEntry<T::BlockNumber>,
+    ) -> bool {
+        let limit = match Self::price_change_limit(src) {
+            Some(limit) => limit,
+            None => return true,
+        };
+        let maybe_limited = Self::oracle((src, assets, LastBlock))
+            .and_then(|(previous, _)| limit_price_change(previous.price, oracle_entry.price, limit.max_change));
+        match (limit.action, maybe_limited) {
+            (PriceLimitAction::Ignore, None) => {
+                // the price is within the limit again, so the ignored updates are no longer consecutive
+                if IgnoredUpdates::<T>::contains_key(src, assets) {
+                    IgnoredUpdates::<T>::remove(src, assets);
+                }
+                true
+            }
+            (_, None) => true,
+            (PriceLimitAction::Clamp, Some(limited_price)) => {
+                Self::deposit_event(Event::PriceClamped {
+                    source: src,
+                    assets,
+                    price: oracle_entry.price,
+                    clamped_price: limited_price,
+                });
+                oracle_entry.price = limited_price;
+                true
+            }
+            (PriceLimitAction::Ignore, Some(_)) => {
+                let ignored = Self::ignored_updates(src, assets);
+                if ignored >= T::MaxIgnoredUpdates::get() {
+                    IgnoredUpdates::<T>::remove(src, assets);
+                    Self::deposit_event(Event::PriceUpdateAccepted {
+                        source: src,
+                        assets,
+                        price: oracle_entry.price,
+                    });
+                    true
+                } else {
+                    IgnoredUpdates::<T>::insert(src, assets, ignored.saturating_add(1));
+                    Self::deposit_event(Event::PriceUpdateIgnored {
+                        source: src,
+                        assets,
+                        price: oracle_entry.price,
+                    });
+                    false
+                }
+            }
         }
     }
 
@@ -340,11 +819,17 @@ impl<T: Config> Pallet<T> {
     ///
     /// The value will be up to date until the parent block, thus excluding trading data from the
     /// current block. Note: It does not update the values in storage.
+    ///
+    /// Returns `None` for periods that are not supported.
     fn get_updated_entry(
         src: Source,
         assets: (AssetId, AssetId),
         period: OraclePeriod,
     ) -> Option<(OracleEntry<T::BlockNumber>, T::BlockNumber)> {
+        // Oracles of periods that are no longer supported are outdated and about to be removed.
+        if !Self::is_supported_period(period) {
+            return None;
+        }
         let parent = T::BlockNumberProvider::current_block_number().saturating_sub(One::one());
         // First get the `LastBlock` oracle to calculate the updated values for the others.
         let (last_block, last_block_init) = Self::last_block_oracle(src, assets, parent)?;
@@ -361,6 +846,68 @@ impl<T: Config> Pallet<T> {
         }
         .map(|return_entry| (return_entry, init))
     }
+
+    /// Return the updated oracle entry for the given source, assets and period together with the
+    /// block number of its initialization.
+    ///
+    /// Like `AggregatedOracle::get_entry` the entry is updated to the parent block and adjusted to
+    /// the order of the given assets. Meant to be used by runtime APIs.
+    pub fn get_entry_with_init(
+        src: Source,
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+    ) -> Option<(OracleEntry<T::BlockNumber>, T::BlockNumber)> {
+        Self::get_updated_entry(src, ordered_pair(asset_a, asset_b), period).map(|(entry, initialized)| {
+            if (asset_a, asset_b) != ordered_pair(asset_a, asset_b) {
+                (entry.inverted(), initialized)
+            } else {
+                (entry, initialized)
+            }
+        })
+    }
+
+    /// Return the updated oracle entries for all supported periods of the given source and
+    /// assets. Periods without an oracle entry are skipped.
+    ///
+    /// See [`Self::get_entry_with_init`].
+    pub fn get_entries_with_init(
+        src: Source,
+        asset_a: AssetId,
+        asset_b: AssetId,
+    ) -> Vec<(OraclePeriod, OracleEntry<T::BlockNumber>, T::BlockNumber)> {
+        Self::supported_periods()
+            .into_iter()
+            .filter_map(|period| {
+                Self::get_entry_with_init(src, asset_a, asset_b, period).map(|(entry, init)| (period, entry, init))
+            })
+            .collect()
+    }
+
+    /// Return the contribution of each of `sources` to the price of `asset_a/asset_b` aggregated
+    /// across sources. Sources without an oracle for the pair and period are skipped.
+    ///
+    /// Meant for inspecting the result of `CrossSourcePriceOracle`.
+    pub fn get_source_contributions(
+        sources: &[Source],
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+    ) -> Vec<SourceContribution<T::BlockNumber>> {
+        sources
+            .iter()
+            .filter_map(|source| {
+                Self::get_entry(asset_a, asset_b, period, *source)
+                    .ok()
+                    .map(|entry| SourceContribution {
+                        source: *source,
+                        price: entry.price,
+                        liquidity: entry.liquidity.a,
+                        oracle_age: entry.oracle_age,
+                    })
+            })
+            .collect()
+    }
 }
 
 /// A callback handler for trading and liquidity activity that schedules oracle updates.
@@ -375,10 +922,28 @@ impl<T: Config> OnCreatePoolHandler<AssetId> for OnActivityHandler<T> {
 
 /// Calculate the weight contribution of one `on_trade`/`on_liquidity_changed` call towards
 /// `on_finalize`.
+///
+/// The benchmark covers `SupportedPeriods` without price change limits and TWAP observations, the
+/// weight of the custom periods is extrapolated from it and the price change limit check and TWAP
+/// observation are added.
 pub(crate) fn fractional_on_finalize_weight<T: Config>(max_entries: u32) -> Weight {
-    T::WeightInfo::on_finalize_multiple_tokens(max_entries)
+    let per_entry = T::WeightInfo::on_finalize_multiple_tokens(max_entries)
         .saturating_sub(T::WeightInfo::on_finalize_no_entry())
-        .saturating_div(max_entries.into())
+        .saturating_div(max_entries.into());
+    let benchmarked_periods = T::SupportedPeriods::get().len().max(1) as u64;
+    let custom_periods = CustomPeriods::<T>::decode_len().unwrap_or(0) as u64;
+    // + price change limit, previous `LastBlock` oracle and ignored updates reads + ignored updates write
+    let price_change_limit = T::DbWeight::get().reads_writes(3, 1);
+    // + TWAP observations read and write
+    let twap_observation = T::DbWeight::get().reads_writes(1, 1);
+    per_entry
+        .saturating_add(price_change_limit)
+        .saturating_add(twap_observation)
+        .saturating_add(
+            per_entry
+                .saturating_div(benchmarked_periods)
+                .saturating_mul(custom_periods),
+        )
 }
 
 impl<T: Config> OnTradeHandler<AssetId, Balance> for OnActivityHandler<T> {
@@ -412,9 +977,10 @@ impl<T: Config> OnTradeHandler<AssetId, Balance> for OnActivityHandler<T> {
 
     fn on_trade_weight() -> Weight {
         let max_entries = T::MaxUniqueEntries::get();
-        // on_trade + on_finalize / max_entries
+        // on_trade + on_finalize / max_entries + whitelist check + custom periods read
         T::WeightInfo::on_trade_multiple_tokens(max_entries)
             .saturating_add(fractional_on_finalize_weight::<T>(max_entries))
+            .saturating_add(T::DbWeight::get().reads(2))
     }
 }
 
@@ -453,9 +1019,10 @@ impl<T: Config> OnLiquidityChangedHandler<AssetId, Balance> for OnActivityHandle
 
     fn on_liquidity_changed_weight() -> Weight {
         let max_entries = T::MaxUniqueEntries::get();
-        // on_liquidity + on_finalize / max_entries
+        // on_liquidity + on_finalize / max_entries + whitelist check + custom periods read
         T::WeightInfo::on_liquidity_changed_multiple_tokens(max_entries)
             .saturating_add(fractional_on_finalize_weight::<T>(max_entries))
+            .saturating_add(T::DbWeight::get().reads(2))
     }
 }
 
@@ -518,6 +1085,10 @@ pub enum OracleError {
     NotPresent,
     /// The oracle is not defined if the asset ids are the same.
     SameAsset,
+    /// The route does not connect the requested assets.
+    InvalidRoute,
+    /// The price derived along the route cannot be represented.
+    PriceOverflow,
 }
 
 impl<T: Config> AggregatedOracle<AssetId, Balance, T::BlockNumber, Price> for Pallet<T> {
@@ -537,20 +1108,14 @@ impl<T: Config> AggregatedOracle<AssetId, Balance, T::BlockNumber, Price> for Pa
         if asset_a == asset_b {
             return Err(OracleError::SameAsset);
         };
-        Self::get_updated_entry(source, ordered_pair(asset_a, asset_b), period)
+        Self::get_entry_with_init(source, asset_a, asset_b, period)
             .ok_or(OracleError::NotPresent)
-            .map(|(entry, initialized)| {
-                let entry = if (asset_a, asset_b) != ordered_pair(asset_a, asset_b) {
-                    entry.inverted()
-                } else {
-                    entry
-                };
-                entry.into_aggregated(initialized)
-            })
+            .map(|(entry, initialized)| entry.into_aggregated(initialized))
     }
 
     fn get_entry_weight() -> Weight {
-        T::WeightInfo::get_entry()
+        // + custom periods read
+        T::WeightInfo::get_entry().saturating_add(T::DbWeight::get().reads(1))
     }
 }
 
@@ -571,3 +1136,199 @@ impl<T: Config> AggregatedPriceOracle<AssetId, T::BlockNumber, Price> for Pallet
         Self::get_entry_weight()
     }
 }
+
+/// Provides the information needed to wrap the pallet with `FreshPriceOracle`. Freshness is judged
+/// by the `LastBlock` oracle which is updated whenever there is activity for the asset pair.
+impl<T: Config> AggregatedPriceInfoOracle<AssetId, T::BlockNumber, Price> for Pallet<T> {
+    type Error = OracleError;
+
+    fn get_price_info(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<OraclePriceInfo<Price, T::BlockNumber>, Self::Error> {
+        let (price, oracle_age) = Self::get_price(asset_a, asset_b, period, source)?;
+        let (last_block, _) =
+            Self::oracle((source, ordered_pair(asset_a, asset_b), LastBlock)).ok_or(OracleError::NotPresent)?;
+        let parent = T::BlockNumberProvider::current_block_number().saturating_sub(One::one());
+        Ok(OraclePriceInfo {
+            price,
+            oracle_age,
+            blocks_since_update: parent.saturating_sub(last_block.timestamp),
+        })
+    }
+
+    fn get_price_info_weight() -> Weight {
+        Self::get_price_weight().saturating_add(T::DbWeight::get().reads(1))
+    }
+}
+
+/// Price oracle providing the time weighted average price (TWAP) of `source` over `TwapWindow` until
+/// the parent block, adjusted to the order of the given assets.
+///
+/// The `period` is not used as the TWAP is always calculated over the configured `TwapWindow`. The
+/// returned oracle age is the age of the oldest observation used. Fails with `NotPresent` if the
+/// recorded observations do not cover the whole window.
+pub struct TwapOracle<T>(PhantomData<T>);
+
+impl<T: Config> AggregatedPriceOracle<AssetId, T::BlockNumber, Price> for TwapOracle<T> {
+    type Error = OracleError;
+
+    fn get_price(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        _period: OraclePeriod,
+        source: Source,
+    ) -> Result<(Price, T::BlockNumber), Self::Error> {
+        if asset_a == asset_b {
+            return Err(OracleError::SameAsset);
+        };
+        Pallet::<T>::get_twap_for_pair(source, ordered_pair(asset_a, asset_b))
+            .ok_or(OracleError::NotPresent)
+            .map(|(price, age)| {
+                if (asset_a, asset_b) != ordered_pair(asset_a, asset_b) {
+                    (invert_price(price), age)
+                } else {
+                    (price, age)
+                }
+            })
+    }
+
+    fn get_price_weight() -> Weight {
+        // reads fewer storage items than `get_entry`
+        T::WeightInfo::get_entry()
+    }
+}
+
+/// Provides the information needed to wrap the TWAP oracle with `FreshPriceOracle`. Like for the
+/// EMA oracles freshness is judged by the `LastBlock` oracle.
+impl<T: Config> AggregatedPriceInfoOracle<AssetId, T::BlockNumber, Price> for TwapOracle<T> {
+    type Error = OracleError;
+
+    fn get_price_info(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<OraclePriceInfo<Price, T::BlockNumber>, Self::Error> {
+        let (price, oracle_age) = Self::get_price(asset_a, asset_b, period, source)?;
+        let (last_block, _) =
+            Pallet::<T>::oracle((source, ordered_pair(asset_a, asset_b), LastBlock)).ok_or(OracleError::NotPresent)?;
+        let parent = T::BlockNumberProvider::current_block_number().saturating_sub(One::one());
+        Ok(OraclePriceInfo {
+            price,
+            oracle_age,
+            blocks_since_update: parent.saturating_sub(last_block.timestamp),
+        })
+    }
+
+    fn get_price_info_weight() -> Weight {
+        Self::get_price_weight().saturating_add(T::DbWeight::get().reads(1))
+    }
+}
+
+/// Price oracle that derives prices for asset pairs without a direct oracle by chaining the oracles
+/// along the route provided by `Routes`. E.g. the price `A/B` can be derived as `A/LRNA * LRNA/B`.
+///
+/// The returned oracle age is the minimum age of the oracles along the route.
+pub struct DerivedPriceOracle<T, Routes>(PhantomData<(T, Routes)>);
+
+impl<T: Config, Routes: OracleRouteProvider<AssetId>> AggregatedPriceOracle<AssetId, T::BlockNumber, Price>
+    for DerivedPriceOracle<T, Routes>
+{
+    type Error = OracleError;
+
+    fn get_price(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<(Price, T::BlockNumber), Self::Error> {
+        if asset_a == asset_b {
+            return Err(OracleError::SameAsset);
+        };
+        let route = Routes::route(source, asset_a, asset_b).ok_or(OracleError::NotPresent)?;
+        // routes longer than `max_hops` are not covered by the weight
+        if route.len() > Routes::max_hops() as usize || !is_route_between(&route, asset_a, asset_b) {
+            return Err(OracleError::InvalidRoute);
+        }
+
+        let mut derived: Option<(Price, T::BlockNumber)> = None;
+        for hop in route {
+            let (price, age) = Pallet::<T>::get_price(hop.asset_in, hop.asset_out, period, hop.source)?;
+            derived = Some(match derived {
+                None => (price, age),
+                Some((acc_price, acc_age)) => (
+                    multiply_prices(acc_price, price).ok_or(OracleError::PriceOverflow)?,
+                    acc_age.min(age),
+                ),
+            });
+        }
+        derived.ok_or(OracleError::NotPresent)
+    }
+
+    fn get_price_weight() -> Weight {
+        Pallet::<T>::get_price_weight().saturating_mul(Routes::max_hops().into())
+    }
+}
+
+/// Price oracle that aggregates the prices of an asset pair across all `Sources` according to
+/// `Mode`, e.g. the liquidity weighted average of the Omnipool and XYK oracles.
+///
+/// The `source` passed to `get_price` is ignored. Sources without an oracle for the pair are
+/// skipped and the returned oracle age is the minimum age of the contributing oracles. Prices are
+/// aggregated in the canonical order of the pair (see `ordered_pair`) and inverted for the reversed
+/// order, so the price of `b/a` is always the reciprocal of the price of `a/b`. The individual
+/// contributions can be inspected via `Pallet::get_source_contributions`.
+pub struct CrossSourcePriceOracle<T, Sources, Mode>(PhantomData<(T, Sources, Mode)>);
+
+impl<T: Config, Sources: Get<Vec<Source>>, Mode: Get<AggregationMode>>
+    AggregatedPriceOracle<AssetId, T::BlockNumber, Price> for CrossSourcePriceOracle<T, Sources, Mode>
+{
+    type Error = OracleError;
+
+    fn get_price(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        _source: Source,
+    ) -> Result<(Price, T::BlockNumber), Self::Error> {
+        if asset_a == asset_b {
+            return Err(OracleError::SameAsset);
+        };
+        let (ordered_a, ordered_b) = ordered_pair(asset_a, asset_b);
+        let contributions = Pallet::<T>::get_source_contributions(&Sources::get(), ordered_a, ordered_b, period);
+        let oracle_age = contributions
+            .iter()
+            .map(|contribution| contribution.oracle_age)
+            .min()
+            .ok_or(OracleError::NotPresent)?;
+        let prices: Vec<(Price, Balance)> = contributions
+            .into_iter()
+            .map(|contribution| (contribution.price, contribution.liquidity))
+            .collect();
+        let price = aggregate_prices(Mode::get(), &prices).ok_or(OracleError::PriceOverflow)?;
+        if (asset_a, asset_b) != (ordered_a, ordered_b) {
+            Ok((invert_price(price), oracle_age))
+        } else {
+            Ok((price, oracle_age))
+        }
+    }
+
+    fn get_price_weight() -> Weight {
+        Pallet::<T>::get_entry_weight().saturating_mul(Sources::get().len() as u64)
+    }
+}
+
+/// Check that `route` is not empty and leads from `asset_a` to `asset_b` without gaps.
+fn is_route_between(route: &[OracleRouteHop<AssetId>], asset_a: AssetId, asset_b: AssetId) -> bool {
+    match (route.first(), route.last()) {
+        (Some(first), Some(last)) => {
+            first.asset_in == asset_a
+                && last.asset_out == asset_b
+                && route.windows(2).all(|hops| hops[0].asset_out == hops[1].asset_in)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,177 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::parameter_types;
+
+const OTHER_SOURCE: Source = *b"othersrc";
+const THIRD_SOURCE: Source = *b"thirdsrc";
+
+parameter_types! {
+    pub Sources: Vec<Source> = vec![SOURCE, OTHER_SOURCE, THIRD_SOURCE];
+    pub const WeightedAverage: AggregationMode = AggregationMode::LiquidityWeightedAverage;
+    pub const Median: AggregationMode = AggregationMode::Median;
+}
+
+type WeightedAverageOracle = CrossSourcePriceOracle<Test, Sources, WeightedAverage>;
+type MedianOracle = CrossSourcePriceOracle<Test, Sources, Median>;
+
+fn as_rational(price: Price) -> Rational {
+    Rational::from(Into::<(u128, u128)>::into(price))
+}
+
+fn with_two_sources() -> sp_io::TestExternalities {
+    ExtBuilder::default()
+        .with_initial_data(vec![
+            (SOURCE, (HDX, DOT), (2_000, 1_000).into(), Liquidity::new(2_000, 1_000)),
+            (
+                OTHER_SOURCE,
+                (HDX, DOT),
+                (6_000, 1_500).into(),
+                Liquidity::new(6_000, 1_500),
+            ),
+        ])
+        .build()
+}
+
+#[test]
+fn aggregate_prices_should_weight_by_liquidity() {
+    let prices = [(Price::new(2, 1), 2_000), (Price::new(4, 1), 6_000)];
+    let price = aggregate_prices(AggregationMode::LiquidityWeightedAverage, &prices).unwrap();
+    assert_eq!(as_rational(price), Rational::from((7, 2)));
+}
+
+#[test]
+fn aggregate_prices_should_use_equal_weights_without_liquidity() {
+    let prices = [(Price::new(2, 1), 0), (Price::new(4, 1), 0)];
+    let price = aggregate_prices(AggregationMode::LiquidityWeightedAverage, &prices).unwrap();
+    assert_eq!(as_rational(price), Rational::from(3));
+}
+
+#[test]
+fn aggregate_prices_should_return_median() {
+    let odd = [(Price::new(2, 1), 1), (Price::new(10, 1), 1), (Price::new(4, 1), 1_000)];
+    let price = aggregate_prices(AggregationMode::Median, &odd).unwrap();
+    assert_eq!(as_rational(price), Rational::from(4));
+
+    let even = [(Price::new(2, 1), 1), (Price::new(4, 1), 1)];
+    let price = aggregate_prices(AggregationMode::Median, &even).unwrap();
+    assert_eq!(as_rational(price), Rational::from(3));
+}
+
+#[test]
+fn aggregate_prices_should_return_none_without_prices() {
+    assert_eq!(aggregate_prices(AggregationMode::Median, &[]), None);
+    assert_eq!(aggregate_prices(AggregationMode::LiquidityWeightedAverage, &[]), None);
+}
+
+#[test]
+fn source_contributions_should_skip_sources_without_oracle() {
+    with_two_sources().execute_with(|| {
+        System::set_block_number(5);
+        assert_eq!(
+            EmaOracle::get_source_contributions(&Sources::get(), HDX, DOT, LastBlock),
+            vec![
+                SourceContribution {
+                    source: SOURCE,
+                    price: Price::new(2_000, 1_000),
+                    liquidity: 2_000,
+                    oracle_age: 4,
+                },
+                SourceContribution {
+                    source: OTHER_SOURCE,
+                    price: Price::new(6_000, 1_500),
+                    liquidity: 6_000,
+                    oracle_age: 4,
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn source_contributions_should_be_adjusted_to_asset_order() {
+    with_two_sources().execute_with(|| {
+        System::set_block_number(5);
+        let contributions = EmaOracle::get_source_contributions(&Sources::get(), DOT, HDX, LastBlock);
+        assert_eq!(contributions[0].price, Price::new(1_000, 2_000));
+        assert_eq!(contributions[0].liquidity, 1_000);
+    });
+}
+
+#[test]
+fn cross_source_oracle_should_aggregate_prices() {
+    with_two_sources().execute_with(|| {
+        System::set_block_number(5);
+        let (price, age) = WeightedAverageOracle::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from((7, 2)));
+        assert_eq!(age, 4);
+
+        let (price, _) = MedianOracle::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from(3));
+
+        let (inverted, _) = MedianOracle::get_price(DOT, HDX, LastBlock, SOURCE).unwrap();
+        // reciprocal of the median of the ordered pair
+        assert_eq!(as_rational(inverted), Rational::from((1, 3)));
+    });
+}
+
+#[test]
+fn cross_source_oracle_should_return_reciprocal_price_for_reversed_pair() {
+    with_two_sources().execute_with(|| {
+        System::set_block_number(5);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            THIRD_SOURCE,
+            DOT,
+            HDX,
+            1_000,
+            3_000,
+            5_000,
+            7_000
+        ));
+        EmaOracle::on_finalize(5);
+        System::set_block_number(6);
+
+        for (price, inverted) in [
+            (
+                WeightedAverageOracle::get_price(HDX, DOT, LastBlock, SOURCE).unwrap().0,
+                WeightedAverageOracle::get_price(DOT, HDX, LastBlock, SOURCE).unwrap().0,
+            ),
+            (
+                MedianOracle::get_price(HDX, DOT, LastBlock, SOURCE).unwrap().0,
+                MedianOracle::get_price(DOT, HDX, LastBlock, SOURCE).unwrap().0,
+            ),
+        ] {
+            assert_eq!(as_rational(price) * as_rational(inverted), Rational::from(1));
+        }
+    });
+}
+
+#[test]
+fn cross_source_oracle_should_fail_without_any_oracle() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            MedianOracle::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(OracleError::NotPresent)
+        );
+        assert_eq!(
+            MedianOracle::get_price(HDX, HDX, LastBlock, SOURCE),
+            Err(OracleError::SameAsset)
+        );
+    });
+}
@@ -0,0 +1,271 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::sp_runtime::DispatchError::BadOrigin;
+
+const TWO_DAYS: u32 = 2 * 14_400;
+
+fn custom(periods: Vec<u32>) -> BoundedVec<u32, ConstU32<MAX_CUSTOM_PERIODS>> {
+    periods.try_into().unwrap()
+}
+
+#[test]
+fn set_custom_periods_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        assert_eq!(EmaOracle::custom_periods(), custom(vec![TWO_DAYS]));
+        System::assert_last_event(
+            Event::<Test>::CustomPeriodsSet {
+                periods: custom(vec![TWO_DAYS]),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn set_custom_periods_should_fail_when_called_by_non_authority() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            EmaOracle::set_custom_periods(RuntimeOrigin::signed(1), custom(vec![TWO_DAYS])),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn set_custom_periods_should_reject_zero_and_duplicate_periods() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![TWO_DAYS, 0])),
+            Error::<Test>::InvalidCustomPeriods
+        );
+        assert_noop!(
+            EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![TWO_DAYS, TWO_DAYS])),
+            Error::<Test>::InvalidCustomPeriods
+        );
+    });
+}
+
+#[test]
+fn supported_periods_should_include_custom_periods_once() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS, THIRTY_DAYS])
+        ));
+        let mut expected = supported_periods().into_inner();
+        expected.push(Custom(TWO_DAYS));
+        assert_eq!(EmaOracle::supported_periods(), expected);
+    });
+}
+
+#[test]
+fn custom_period_oracle_should_be_updated_once_set() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        assert_eq!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)), None);
+
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+
+        System::set_block_number(2);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(2);
+        assert!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)).is_some());
+
+        System::set_block_number(3);
+        let periods: Vec<OraclePeriod> = EmaOracle::get_entries_with_init(SOURCE, HDX, DOT)
+            .into_iter()
+            .map(|(period, _, _)| period)
+            .collect();
+        assert!(periods.contains(&Custom(TWO_DAYS)));
+    });
+}
+
+#[test]
+fn remove_oracle_should_clear_custom_period_oracle() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        assert_ok!(EmaOracle::add_oracle(RuntimeOrigin::root(), SOURCE, (HDX, DOT)));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        assert!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)).is_some());
+
+        assert_ok!(EmaOracle::remove_oracle(RuntimeOrigin::root(), SOURCE, (HDX, DOT)));
+        assert_eq!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)), None);
+    });
+}
+
+#[test]
+fn remove_oracle_should_clear_oracles_of_periods_no_longer_supported() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        assert_ok!(EmaOracle::add_oracle(RuntimeOrigin::root(), SOURCE, (HDX, DOT)));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        assert_ok!(EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![])));
+
+        assert_ok!(EmaOracle::remove_oracle(RuntimeOrigin::root(), SOURCE, (HDX, DOT)));
+        assert_eq!(
+            Oracles::<Test>::iter_prefix((SOURCE, ordered_pair(HDX, DOT))).count(),
+            0
+        );
+    });
+}
+
+#[test]
+fn custom_periods_should_add_to_on_trade_weight() {
+    new_test_ext().execute_with(|| {
+        let weight = <OnActivityHandler<Test> as OnTradeHandler<AssetId, Balance>>::on_trade_weight();
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        assert!(
+            <OnActivityHandler<Test> as OnTradeHandler<AssetId, Balance>>::on_trade_weight().ref_time()
+                > weight.ref_time()
+        );
+    });
+}
+
+#[test]
+fn oracle_of_removed_custom_period_should_not_be_returned() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        System::set_block_number(2);
+        assert!(EmaOracle::get_entry(HDX, DOT, Custom(TWO_DAYS), SOURCE).is_ok());
+
+        assert_ok!(EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![])));
+
+        assert!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)).is_some());
+        assert_eq!(
+            EmaOracle::get_entry(HDX, DOT, Custom(TWO_DAYS), SOURCE),
+            Err(OracleError::NotPresent)
+        );
+    });
+}
+
+#[test]
+fn on_idle_should_remove_oracles_of_removed_custom_periods() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, ACA, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        assert_ok!(EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![])));
+        assert!(StaleOraclesCursor::<Test>::exists());
+
+        EmaOracle::on_idle(2, Weight::MAX);
+
+        assert_eq!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)), None);
+        assert_eq!(
+            Oracles::<Test>::get((SOURCE, ordered_pair(HDX, ACA), Custom(TWO_DAYS))),
+            None
+        );
+        assert!(get_oracle_entry(HDX, DOT, LastBlock).is_some());
+        assert!(!StaleOraclesCursor::<Test>::exists());
+    });
+}
+
+#[test]
+fn on_idle_should_continue_removing_stale_oracles_from_cursor() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        assert_ok!(EmaOracle::set_custom_periods(RuntimeOrigin::root(), custom(vec![])));
+
+        let stored = Oracles::<Test>::iter_keys().count() as u32;
+        let one_entry = <() as crate::weights::WeightInfo>::remove_stale_oracles(1);
+        assert_eq!(EmaOracle::on_idle(2, one_entry), one_entry);
+        assert!(StaleOraclesCursor::<Test>::get().map_or(false, |cursor| !cursor.is_empty()));
+
+        for _ in 1..stored {
+            EmaOracle::on_idle(2, one_entry);
+        }
+        EmaOracle::on_idle(2, one_entry);
+
+        assert_eq!(get_oracle_entry(HDX, DOT, Custom(TWO_DAYS)), None);
+        assert_eq!(Oracles::<Test>::iter_keys().count() as u32, stored - 1);
+        assert!(!StaleOraclesCursor::<Test>::exists());
+    });
+}
+
+#[test]
+fn set_custom_periods_should_not_start_removal_when_no_period_is_removed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS])
+        ));
+        assert_ok!(EmaOracle::set_custom_periods(
+            RuntimeOrigin::root(),
+            custom(vec![TWO_DAYS, THIRTY_DAYS / 2])
+        ));
+        assert!(!StaleOraclesCursor::<Test>::exists());
+    });
+}
@@ -0,0 +1,158 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::parameter_types;
+use hydradx_traits::RouteThroughAsset;
+
+parameter_types! {
+    pub const HubAsset: AssetId = HDX;
+}
+
+type DerivedOracle = DerivedPriceOracle<Test, RouteThroughAsset<HubAsset>>;
+
+/// Route provider returning a route that does not end in the requested asset.
+struct BrokenRoute;
+impl OracleRouteProvider<AssetId> for BrokenRoute {
+    fn route(source: Source, asset_a: AssetId, _asset_b: AssetId) -> Option<Vec<OracleRouteHop<AssetId>>> {
+        Some(vec![OracleRouteHop {
+            source,
+            asset_in: asset_a,
+            asset_out: HDX,
+        }])
+    }
+
+    fn max_hops() -> u32 {
+        1
+    }
+}
+
+#[test]
+fn derived_price_should_chain_oracles_through_hub_asset() {
+    ExtBuilder::default()
+        .with_initial_data(vec![
+            (SOURCE, (DOT, HDX), (2, 1).into(), Liquidity::new(2_000, 1_000)),
+            (SOURCE, (HDX, ACA), (1, 4).into(), Liquidity::new(1_000, 4_000)),
+        ])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(2);
+            // DOT/HDX * HDX/ACA = 2 * 1/4
+            assert_eq!(
+                DerivedOracle::get_price(DOT, ACA, TenMinutes, SOURCE),
+                Ok((Price::new(2, 4), 1))
+            );
+            assert_eq!(
+                DerivedOracle::get_price(ACA, DOT, TenMinutes, SOURCE),
+                Ok((Price::new(4, 2), 1))
+            );
+        });
+}
+
+#[test]
+fn derived_price_should_use_direct_oracle_for_hub_asset() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(SOURCE, (DOT, HDX), (2, 1).into(), Liquidity::new(2_000, 1_000))])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(2);
+            assert_eq!(
+                DerivedOracle::get_price(DOT, HDX, LastBlock, SOURCE),
+                EmaOracle::get_price(DOT, HDX, LastBlock, SOURCE)
+            );
+        });
+}
+
+#[test]
+fn derived_price_should_return_minimum_oracle_age() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(SOURCE, (DOT, HDX), (2, 1).into(), Liquidity::new(2_000, 1_000))])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(10);
+            assert_ok!(OnActivityHandler::<Test>::on_trade(
+                SOURCE, HDX, ACA, 1_000, 4_000, 1_000, 4_000
+            ));
+            EmaOracle::on_finalize(10);
+
+            System::set_block_number(20);
+            let (_, dot_hdx_age) = EmaOracle::get_price(DOT, HDX, TenMinutes, SOURCE).unwrap();
+            let (_, hdx_aca_age) = EmaOracle::get_price(HDX, ACA, TenMinutes, SOURCE).unwrap();
+            assert_eq!((dot_hdx_age, hdx_aca_age), (19, 9));
+
+            assert_eq!(DerivedOracle::get_price(DOT, ACA, TenMinutes, SOURCE).unwrap().1, 9);
+        });
+}
+
+#[test]
+fn derived_price_should_fail_when_oracle_along_route_is_missing() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(SOURCE, (DOT, HDX), (2, 1).into(), Liquidity::new(2_000, 1_000))])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(2);
+            assert_eq!(
+                DerivedOracle::get_price(DOT, ACA, TenMinutes, SOURCE),
+                Err(OracleError::NotPresent)
+            );
+        });
+}
+
+#[test]
+fn derived_price_should_fail_for_same_asset() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            DerivedOracle::get_price(DOT, DOT, TenMinutes, SOURCE),
+            Err(OracleError::SameAsset)
+        );
+    });
+}
+
+#[test]
+fn derived_price_should_fail_when_route_does_not_connect_assets() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(SOURCE, (DOT, HDX), (2, 1).into(), Liquidity::new(2_000, 1_000))])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(2);
+            assert_eq!(
+                DerivedPriceOracle::<Test, BrokenRoute>::get_price(DOT, ACA, TenMinutes, SOURCE),
+                Err(OracleError::InvalidRoute)
+            );
+        });
+}
+
+#[test]
+fn multiply_prices_should_round_large_values() {
+    assert_eq!(
+        multiply_prices(Price::new(2, 3), Price::new(3, 4)),
+        Some(Price::new(6, 12))
+    );
+
+    let big = Price::new(u128::MAX, 1);
+    assert_eq!(
+        multiply_prices(big, Price::new(1, u128::MAX)),
+        Some(Price::new(u128::MAX, u128::MAX))
+    );
+    assert_eq!(
+        multiply_prices(big, Price::new(2, u128::MAX)),
+        Some(Price::new(u128::MAX, u128::MAX >> 1))
+    );
+
+    assert_eq!(multiply_prices(big, big), None);
+}
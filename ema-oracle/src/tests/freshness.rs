@@ -0,0 +1,146 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::traits::ConstU64;
+use hydradx_traits::{FreshPriceOracle, FreshnessError, PriceConversionError, PriceInfoOracleAdapter};
+use sp_arithmetic::FixedU128;
+
+type FreshOracle = FreshPriceOracle<EmaOracle, ConstU64<5>, ConstU64<3>>;
+/// The oracle as used by consumers expecting fixed point prices, e.g. the multi-payment pallet.
+type FixedPointOracle = PriceInfoOracleAdapter<EmaOracle, AssetId, Price>;
+type FreshFixedPointOracle = FreshPriceOracle<FixedPointOracle, ConstU64<5>, ConstU64<3>>;
+
+fn with_hdx_dot_oracle() -> sp_io::TestExternalities {
+    ExtBuilder::default()
+        .with_initial_data(vec![(
+            SOURCE,
+            (HDX, DOT),
+            (2_000, 1_000).into(),
+            Liquidity::new(2_000, 1_000),
+        )])
+        .build()
+}
+
+#[test]
+fn get_price_info_should_report_blocks_since_update() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(5);
+        assert_eq!(
+            EmaOracle::get_price_info(HDX, DOT, TenMinutes, SOURCE),
+            Ok(OraclePriceInfo {
+                price: Price::new(2_000, 1_000),
+                oracle_age: 4,
+                blocks_since_update: 4,
+            })
+        );
+    });
+}
+
+#[test]
+fn fresh_oracle_should_return_price_of_settled_and_recent_oracle() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(5);
+        assert_eq!(
+            FreshOracle::get_price(HDX, DOT, LastBlock, SOURCE),
+            Ok((Price::new(2_000, 1_000), 4))
+        );
+    });
+}
+
+#[test]
+fn fresh_oracle_should_fail_when_oracle_is_too_young() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(2);
+        assert_eq!(
+            FreshOracle::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(FreshnessError::NotReady)
+        );
+    });
+}
+
+#[test]
+fn fresh_oracle_should_fail_when_oracle_is_stale() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(7);
+        assert_eq!(
+            FreshOracle::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(FreshnessError::Stale)
+        );
+    });
+}
+
+#[test]
+fn fresh_oracle_should_accept_oracle_again_after_update() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(6);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(6);
+        System::set_block_number(7);
+
+        assert!(FreshOracle::get_price(HDX, DOT, LastBlock, SOURCE).is_ok());
+    });
+}
+
+#[test]
+fn fresh_oracle_should_forward_oracle_errors() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        assert_eq!(
+            FreshOracle::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(FreshnessError::Oracle(OracleError::NotPresent))
+        );
+    });
+}
+
+#[test]
+fn adapted_oracle_should_return_fixed_point_price() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(5);
+        let price_info: Result<OraclePriceInfo<FixedU128, u64>, _> =
+            FixedPointOracle::get_price_info(HDX, DOT, TenMinutes, SOURCE);
+        assert_eq!(
+            price_info,
+            Ok(OraclePriceInfo {
+                price: FixedU128::from(2),
+                oracle_age: 4,
+                blocks_since_update: 4,
+            })
+        );
+        let price: Result<(FixedU128, u64), _> = FreshFixedPointOracle::get_price(DOT, HDX, LastBlock, SOURCE);
+        assert_eq!(price, Ok((FixedU128::from_rational(1, 2), 4)));
+    });
+}
+
+#[test]
+fn adapted_oracle_should_check_freshness_and_forward_oracle_errors() {
+    with_hdx_dot_oracle().execute_with(|| {
+        System::set_block_number(7);
+        let stale: Result<(FixedU128, u64), _> = FreshFixedPointOracle::get_price(HDX, DOT, LastBlock, SOURCE);
+        assert_eq!(stale, Err(FreshnessError::Stale));
+        let missing: Result<(FixedU128, u64), _> = FreshFixedPointOracle::get_price(HDX, ACA, LastBlock, SOURCE);
+        assert_eq!(
+            missing,
+            Err(FreshnessError::Oracle(PriceConversionError::Oracle(
+                OracleError::NotPresent
+            )))
+        );
+    });
+}
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::mock::{DOT, HDX};
+use super::mock::{DOT, HDX, UNTRACKED_ASSET};
 use super::*;
 
 use pretty_assertions::assert_eq;
@@ -25,7 +25,11 @@ use frame_support::assert_ok;
 
 // Strategies
 fn valid_asset_ids() -> impl Strategy<Value = (AssetId, AssetId)> {
-    (any::<AssetId>(), any::<AssetId>()).prop_filter("asset ids should not be equal", |(a, b)| a != b)
+    (any::<AssetId>(), any::<AssetId>())
+        .prop_filter("asset ids should not be equal", |(a, b)| a != b)
+        .prop_filter("asset ids should be tracked", |(a, b)| {
+            *a != UNTRACKED_ASSET && *b != UNTRACKED_ASSET
+        })
 }
 
 fn non_zero_amount() -> impl Strategy<Value = Balance> {
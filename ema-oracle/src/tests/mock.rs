@@ -25,8 +25,9 @@ use frame_support::sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
 };
-use frame_support::traits::{Everything, GenesisBuild};
+use frame_support::traits::{Contains, Everything, GenesisBuild};
 use frame_support::BoundedVec;
+use frame_system::EnsureRoot;
 use hydradx_traits::OraclePeriod::{self, *};
 use hydradx_traits::{AssetPairAccountIdFor, Liquidity, Volume};
 use sp_core::H256;
@@ -44,6 +45,10 @@ use crate::MAX_PERIODS;
 pub const HDX: AssetId = 1_000;
 pub const DOT: AssetId = 2_000;
 pub const ACA: AssetId = 3_000;
+/// Number of blocks of a custom 30 day period assuming 6 second blocks.
+pub const THIRTY_DAYS: u32 = 30 * 14_400;
+/// Asset that is not covered by the `OracleWhitelist` filter of the mock runtime.
+pub const UNTRACKED_ASSET: AssetId = 999_999;
 
 pub const ORACLE_ENTRY_1: OracleEntry<BlockNumber> = OracleEntry {
     price: Price::new(2_000, 1_000),
@@ -125,15 +130,27 @@ impl AssetPairAccountIdFor<AssetId, u64> for AssetPairAccountIdTest {
 }
 
 parameter_types! {
-    pub SupportedPeriods: BoundedVec<OraclePeriod, ConstU32<MAX_PERIODS>> = bounded_vec![LastBlock, TenMinutes, Day, Week];
+    pub SupportedPeriods: BoundedVec<OraclePeriod, ConstU32<MAX_PERIODS>> = bounded_vec![LastBlock, TenMinutes, Day, Week, Custom(THIRTY_DAYS)];
+}
+
+pub struct TrackedAssetsFilter;
+impl Contains<(Source, AssetId, AssetId)> for TrackedAssetsFilter {
+    fn contains(t: &(Source, AssetId, AssetId)) -> bool {
+        t.1 != UNTRACKED_ASSET && t.2 != UNTRACKED_ASSET
+    }
 }
 
 impl Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
+    type AuthorityOrigin = EnsureRoot<u64>;
     type BlockNumberProvider = System;
     type SupportedPeriods = SupportedPeriods;
+    type OracleWhitelist = TrackedAssetsFilter;
     type MaxUniqueEntries = ConstU32<45>;
+    type TwapWindow = ConstU32<10>;
+    type MaxTwapObservations = ConstU32<10>;
+    type MaxIgnoredUpdates = ConstU32<2>;
 }
 
 pub type InitialDataEntry = (Source, (AssetId, AssetId), Price, Liquidity<Balance>);
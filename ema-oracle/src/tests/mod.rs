@@ -15,13 +15,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cross_source;
+mod custom_periods;
+mod derived;
+mod freshness;
 mod invariants;
 mod mock;
+mod price_change_limit;
+mod twap;
+mod whitelist;
 
 use super::*;
 pub use mock::{
     BlockNumber, EmaOracle, ExtBuilder, RuntimeEvent as TestEvent, RuntimeOrigin, System, Test, ACA, DOT, HDX,
-    ORACLE_ENTRY_1, ORACLE_ENTRY_2,
+    ORACLE_ENTRY_1, ORACLE_ENTRY_2, THIRTY_DAYS, UNTRACKED_ASSET,
 };
 
 use frame_support::{assert_noop, assert_ok};
@@ -620,6 +627,55 @@ fn get_entry_works() {
     });
 }
 
+#[test]
+fn get_entry_with_init_should_return_updated_entry_and_init_block() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        System::set_block_number(100);
+
+        let (entry, init) = EmaOracle::get_entry_with_init(SOURCE, HDX, DOT, TenMinutes).unwrap();
+        assert_eq!(init, 1);
+        assert_eq!(entry.timestamp, 99);
+        assert_eq!(
+            entry.clone().into_aggregated(init),
+            EmaOracle::get_entry(HDX, DOT, TenMinutes, SOURCE).unwrap()
+        );
+
+        let (inverted_entry, _) = EmaOracle::get_entry_with_init(SOURCE, DOT, HDX, TenMinutes).unwrap();
+        assert_eq!(inverted_entry, entry.inverted());
+
+        assert_eq!(EmaOracle::get_entry_with_init(SOURCE, HDX, ACA, TenMinutes), None);
+    });
+}
+
+#[test]
+fn get_entries_with_init_should_return_entries_for_all_supported_periods() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+        System::set_block_number(100);
+
+        let entries = EmaOracle::get_entries_with_init(SOURCE, DOT, HDX);
+        let periods: Vec<OraclePeriod> = entries.iter().map(|(period, _, _)| *period).collect();
+        assert_eq!(periods, supported_periods().into_inner());
+        for (period, entry, init) in entries {
+            assert_eq!(
+                Some((entry, init)),
+                EmaOracle::get_entry_with_init(SOURCE, DOT, HDX, period)
+            );
+        }
+
+        assert!(EmaOracle::get_entries_with_init(SOURCE, HDX, ACA).is_empty());
+    });
+}
+
 #[test]
 fn get_price_returns_updated_price() {
     ExtBuilder::default()
@@ -762,4 +818,59 @@ fn check_period_smoothing_factors() {
     let week = smoothing_from_period(7 * days);
     println!("Week: {} (bits: {})", week, week.to_bits());
     assert_eq!(into_smoothing(Week), week);
+
+    // custom periods derive their smoothing factor from the number of blocks
+    assert_eq!(into_smoothing(Custom(days as u32)), into_smoothing(Day));
+    assert_eq!(into_smoothing(Custom(7 * days as u32)), into_smoothing(Week));
+    assert_eq!(
+        into_smoothing(Custom(30 * days as u32)),
+        smoothing_from_period(30 * days)
+    );
+    assert_eq!(into_smoothing(Custom(0)), into_smoothing(LastBlock));
+}
+
+#[test]
+fn oracle_period_encoding_should_be_stable() {
+    use codec::Encode;
+
+    assert_eq!(LastBlock.encode(), vec![0]);
+    assert_eq!(Short.encode(), vec![1]);
+    assert_eq!(TenMinutes.encode(), vec![2]);
+    assert_eq!(Hour.encode(), vec![3]);
+    assert_eq!(Day.encode(), vec![4]);
+    assert_eq!(Week.encode(), vec![5]);
+    assert_eq!(Custom(THIRTY_DAYS).encode(), [vec![6], THIRTY_DAYS.encode()].concat());
+}
+
+#[test]
+fn custom_period_oracle_should_be_updated() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(
+            SOURCE,
+            (HDX, DOT),
+            (1_000_000, 1).into(),
+            Liquidity::new(2_000_000, 2),
+        )])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let on_trade_entry = OracleEntry {
+                price: Price::new(500_000, 1),
+                volume: Volume::default(),
+                liquidity: Liquidity::new(2_000_000, 2),
+                timestamp: 1,
+            };
+            assert_ok!(EmaOracle::on_trade(SOURCE, ordered_pair(HDX, DOT), on_trade_entry));
+            EmaOracle::on_finalize(1);
+
+            System::set_block_number(10_001);
+            let (week_price, _) = EmaOracle::get_price(HDX, DOT, Week, SOURCE).unwrap();
+            let (thirty_days_price, age) = EmaOracle::get_price(HDX, DOT, Custom(THIRTY_DAYS), SOURCE).unwrap();
+            assert_eq!(age, 10_000);
+            // the longer period should converge slower towards the new price
+            let (week_n, week_d): (u128, u128) = week_price.into();
+            let (thirty_n, thirty_d): (u128, u128) = thirty_days_price.into();
+            assert!(Rational::from((thirty_n, thirty_d)) > Rational::from((week_n, week_d)));
+            assert!(Rational::from((thirty_n, thirty_d)) < Rational::from((1_000_000, 1)));
+        });
 }
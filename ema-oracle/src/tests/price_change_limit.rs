@@ -0,0 +1,307 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::sp_runtime::DispatchError::BadOrigin;
+use sp_arithmetic::Permill;
+
+const CLAMP_TEN_PERCENT: PriceChangeLimit = PriceChangeLimit {
+    max_change: Permill::from_percent(10),
+    action: PriceLimitAction::Clamp,
+};
+
+const IGNORE_TEN_PERCENT: PriceChangeLimit = PriceChangeLimit {
+    max_change: Permill::from_percent(10),
+    action: PriceLimitAction::Ignore,
+};
+
+fn with_hdx_dot_oracle() -> sp_io::TestExternalities {
+    ExtBuilder::default()
+        .with_initial_data(vec![(
+            SOURCE,
+            (HDX, DOT),
+            (2_000, 1_000).into(),
+            Liquidity::new(2_000, 1_000),
+        )])
+        .build()
+}
+
+fn last_event() -> TestEvent {
+    System::events().pop().expect("an event should be emitted").event
+}
+
+#[test]
+fn set_price_change_limit_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(CLAMP_TEN_PERCENT)
+        ));
+        assert_eq!(EmaOracle::price_change_limit(SOURCE), Some(CLAMP_TEN_PERCENT));
+        assert_eq!(
+            last_event(),
+            Event::<Test>::PriceChangeLimitSet {
+                source: SOURCE,
+                limit: Some(CLAMP_TEN_PERCENT),
+            }
+            .into()
+        );
+
+        assert_ok!(EmaOracle::set_price_change_limit(RuntimeOrigin::root(), SOURCE, None));
+        assert_eq!(EmaOracle::price_change_limit(SOURCE), None);
+    });
+}
+
+#[test]
+fn set_price_change_limit_should_fail_when_origin_is_not_authority() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            EmaOracle::set_price_change_limit(RuntimeOrigin::signed(1), SOURCE, Some(CLAMP_TEN_PERCENT)),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn price_above_limit_should_be_clamped() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(CLAMP_TEN_PERCENT)
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 250, 4_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        let clamped_price = Price::new(2_000 * 1_100_000, 1_000 * 1_000_000);
+        assert_eq!(get_oracle_entry(HDX, DOT, LastBlock).unwrap().price, clamped_price);
+        assert_eq!(
+            last_event(),
+            Event::<Test>::PriceClamped {
+                source: SOURCE,
+                assets: (HDX, DOT),
+                price: Price::new(4_000, 1_000),
+                clamped_price,
+            }
+            .into()
+        );
+    });
+}
+
+#[test]
+fn price_below_limit_should_be_clamped() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(CLAMP_TEN_PERCENT)
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 1_000, 1_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        let clamped_price = Price::new(2_000 * 900_000, 1_000 * 1_000_000);
+        assert_eq!(get_oracle_entry(HDX, DOT, LastBlock).unwrap().price, clamped_price);
+    });
+}
+
+#[test]
+fn price_within_limit_should_not_be_changed() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(CLAMP_TEN_PERCENT)
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_100, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        assert_eq!(
+            get_oracle_entry(HDX, DOT, LastBlock).unwrap().price,
+            Price::new(2_100, 1_000)
+        );
+        assert!(System::events().is_empty());
+    });
+}
+
+#[test]
+fn update_exceeding_limit_should_be_ignored() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(IGNORE_TEN_PERCENT)
+        ));
+        let entries_before: Vec<_> = supported_periods()
+            .into_iter()
+            .map(|period| get_oracle_entry(HDX, DOT, period))
+            .collect();
+
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 250, 4_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        let entries_after: Vec<_> = supported_periods()
+            .into_iter()
+            .map(|period| get_oracle_entry(HDX, DOT, period))
+            .collect();
+        assert_eq!(entries_before, entries_after);
+        assert_eq!(
+            last_event(),
+            Event::<Test>::PriceUpdateIgnored {
+                source: SOURCE,
+                assets: (HDX, DOT),
+                price: Price::new(4_000, 1_000),
+            }
+            .into()
+        );
+    });
+}
+
+#[test]
+fn limit_should_only_apply_to_configured_source() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            *b"othersrc",
+            Some(IGNORE_TEN_PERCENT)
+        ));
+        System::set_block_number(1);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 250, 4_000, 1_000
+        ));
+        EmaOracle::on_finalize(1);
+
+        assert_eq!(
+            get_oracle_entry(HDX, DOT, LastBlock).unwrap().price,
+            Price::new(4_000, 1_000)
+        );
+    });
+}
+
+/// Trade at a price of 4 HDX/DOT, which exceeds the limit of the genesis price of 2 HDX/DOT.
+fn trade_exceeding_limit(block: BlockNumber) {
+    System::set_block_number(block);
+    assert_ok!(OnActivityHandler::<Test>::on_trade(
+        SOURCE, HDX, DOT, 1_000, 250, 4_000, 1_000
+    ));
+    EmaOracle::on_finalize(block);
+}
+
+#[test]
+fn update_exceeding_limit_should_be_accepted_after_max_ignored_updates() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(IGNORE_TEN_PERCENT)
+        ));
+        let max_ignored = <Test as crate::Config>::MaxIgnoredUpdates::get();
+        for block in 1..=max_ignored {
+            trade_exceeding_limit(block.into());
+            assert_eq!(EmaOracle::ignored_updates(SOURCE, (HDX, DOT)), block);
+            assert_eq!(
+                get_oracle_entry(HDX, DOT, LastBlock).unwrap().price,
+                Price::new(2_000, 1_000)
+            );
+        }
+
+        trade_exceeding_limit((max_ignored + 1).into());
+
+        assert_eq!(
+            get_oracle_entry(HDX, DOT, LastBlock).unwrap().price,
+            Price::new(4_000, 1_000)
+        );
+        assert_eq!(EmaOracle::ignored_updates(SOURCE, (HDX, DOT)), 0);
+        assert_eq!(
+            last_event(),
+            Event::<Test>::PriceUpdateAccepted {
+                source: SOURCE,
+                assets: (HDX, DOT),
+                price: Price::new(4_000, 1_000),
+            }
+            .into()
+        );
+    });
+}
+
+#[test]
+fn update_within_limit_should_reset_ignored_updates() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::set_price_change_limit(
+            RuntimeOrigin::root(),
+            SOURCE,
+            Some(IGNORE_TEN_PERCENT)
+        ));
+        trade_exceeding_limit(1);
+        assert_eq!(EmaOracle::ignored_updates(SOURCE, (HDX, DOT)), 1);
+
+        System::set_block_number(2);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE, HDX, DOT, 1_000, 500, 2_100, 1_000
+        ));
+        EmaOracle::on_finalize(2);
+        assert_eq!(EmaOracle::ignored_updates(SOURCE, (HDX, DOT)), 0);
+
+        let max_ignored = <Test as crate::Config>::MaxIgnoredUpdates::get();
+        for block in 3..3 + max_ignored {
+            trade_exceeding_limit(block.into());
+        }
+        assert_eq!(
+            get_oracle_entry(HDX, DOT, LastBlock).unwrap().price,
+            Price::new(2_100, 1_000)
+        );
+    });
+}
+
+#[test]
+fn limit_price_change_should_not_limit_zero_previous_price() {
+    assert_eq!(
+        limit_price_change(Price::zero(), Price::new(1, 1), Permill::from_percent(1)),
+        None
+    );
+}
+
+#[test]
+fn limit_price_change_should_allow_exact_bounds() {
+    let previous = Price::new(100, 1);
+    assert_eq!(
+        limit_price_change(previous, Price::new(110, 1), Permill::from_percent(10)),
+        None
+    );
+    assert_eq!(
+        limit_price_change(previous, Price::new(90, 1), Permill::from_percent(10)),
+        None
+    );
+    assert_eq!(
+        limit_price_change(previous, Price::new(111, 1), Permill::from_percent(10)),
+        Some(Price::new(100 * 1_100_000, 1_000_000))
+    );
+}
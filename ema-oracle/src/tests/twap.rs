@@ -0,0 +1,192 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+fn with_hdx_dot_oracle() -> sp_io::TestExternalities {
+    ExtBuilder::default()
+        .with_initial_data(vec![(
+            SOURCE,
+            (HDX, DOT),
+            (2_000, 1_000).into(),
+            Liquidity::new(2_000, 1_000),
+        )])
+        .build()
+}
+
+fn as_rational(price: Price) -> Rational {
+    Rational::from(Into::<(u128, u128)>::into(price))
+}
+
+fn trade_at_price(block: BlockNumber, price: (Balance, Balance)) {
+    System::set_block_number(block);
+    assert_ok!(OnActivityHandler::<Test>::on_trade(
+        SOURCE, HDX, DOT, 1_000, 500, price.0, price.1
+    ));
+    EmaOracle::on_finalize(block);
+}
+
+#[test]
+fn calculate_twap_should_weight_prices_by_duration() {
+    let observations = [(0, Price::new(2, 1)), (6, Price::new(4, 1))];
+    let twap = calculate_twap(&observations, 10, 10).unwrap();
+    assert_eq!(as_rational(twap), Rational::from(3));
+}
+
+#[test]
+fn calculate_twap_should_only_consider_window() {
+    let observations = [(0, Price::new(1, 1)), (10, Price::new(2, 1)), (15, Price::new(6, 1))];
+    let twap = calculate_twap(&observations, 19, 10).unwrap();
+    assert_eq!(as_rational(twap), Rational::from(4));
+}
+
+#[test]
+fn calculate_twap_should_return_none_without_observations_in_window() {
+    assert_eq!(calculate_twap(&[], 10, 10), None);
+    assert_eq!(calculate_twap(&[(11, Price::new(1, 1))], 10, 10), None);
+}
+
+#[test]
+fn calculate_twap_should_return_none_when_window_is_not_covered() {
+    let observations = [(2, Price::new(1, 1)), (6, Price::new(2, 1))];
+    assert_eq!(calculate_twap(&observations, 10, 10), None);
+    assert!(calculate_twap(&observations, 11, 10).is_some());
+}
+
+#[test]
+fn genesis_should_record_twap_observation() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_eq!(
+            EmaOracle::twap_observations(SOURCE, (HDX, DOT)).into_inner(),
+            vec![(0, Price::new(2_000, 1_000))]
+        );
+    });
+}
+
+#[test]
+fn twap_oracle_should_return_twap() {
+    with_hdx_dot_oracle().execute_with(|| {
+        trade_at_price(6, (4_000, 1_000));
+        System::set_block_number(11);
+
+        let (price, age) = TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from(3));
+        assert_eq!(age, 10);
+
+        let (inverted, _) = TwapOracle::<Test>::get_price(DOT, HDX, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(inverted), Rational::from((1, 3)));
+    });
+}
+
+#[test]
+fn twap_should_follow_price_after_window_passed() {
+    with_hdx_dot_oracle().execute_with(|| {
+        trade_at_price(1, (4_000, 1_000));
+        System::set_block_number(12);
+
+        let (price, _) = TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from(4));
+    });
+}
+
+#[test]
+fn observations_outside_of_window_should_be_pruned() {
+    with_hdx_dot_oracle().execute_with(|| {
+        trade_at_price(5, (3_000, 1_000));
+        trade_at_price(20, (4_000, 1_000));
+
+        assert_eq!(
+            EmaOracle::twap_observations(SOURCE, (HDX, DOT)).into_inner(),
+            vec![(5, Price::new(3_000, 1_000)), (20, Price::new(4_000, 1_000))]
+        );
+    });
+}
+
+#[test]
+fn twap_should_be_available_after_more_trades_than_max_observations() {
+    with_hdx_dot_oracle().execute_with(|| {
+        let max_observations = <Test as crate::Config>::MaxTwapObservations::get();
+        let last_trade = max_observations as BlockNumber + 5;
+        for block in 1..=last_trade {
+            trade_at_price(block, (4_000, 1_000));
+        }
+        System::set_block_number(last_trade + 1);
+
+        let observations = EmaOracle::twap_observations(SOURCE, (HDX, DOT));
+        assert!(observations.len() <= max_observations as usize);
+        let (price, _) = TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from(4));
+    });
+}
+
+#[test]
+fn twap_oracle_should_ignore_period() {
+    with_hdx_dot_oracle().execute_with(|| {
+        trade_at_price(6, (4_000, 1_000));
+        System::set_block_number(11);
+
+        assert_eq!(
+            TwapOracle::<Test>::get_price(HDX, DOT, Week, SOURCE),
+            TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE)
+        );
+    });
+}
+
+#[test]
+fn twap_oracle_should_fail_without_observations() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(OracleError::NotPresent)
+        );
+        assert_eq!(
+            TwapOracle::<Test>::get_price(HDX, HDX, LastBlock, SOURCE),
+            Err(OracleError::SameAsset)
+        );
+    });
+}
+
+#[test]
+fn twap_oracle_should_fail_when_observations_do_not_cover_window() {
+    new_test_ext().execute_with(|| {
+        trade_at_price(5, (4_000, 1_000));
+        System::set_block_number(10);
+        assert_eq!(
+            TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE),
+            Err(OracleError::NotPresent)
+        );
+
+        System::set_block_number(15);
+        let (price, _) = TwapOracle::<Test>::get_price(HDX, DOT, LastBlock, SOURCE).unwrap();
+        assert_eq!(as_rational(price), Rational::from(4));
+    });
+}
+
+#[test]
+fn remove_oracle_should_clear_twap_observations() {
+    with_hdx_dot_oracle().execute_with(|| {
+        assert_ok!(EmaOracle::remove_oracle(RuntimeOrigin::root(), SOURCE, (HDX, DOT)));
+        assert!(EmaOracle::twap_observations(SOURCE, (HDX, DOT)).is_empty());
+    });
+}
+
+#[test]
+fn integrity_test_should_accept_observations_covering_window() {
+    new_test_ext().execute_with(|| {
+        <EmaOracle as Hooks<BlockNumber>>::integrity_test();
+    });
+}
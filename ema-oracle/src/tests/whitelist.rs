@@ -0,0 +1,228 @@
+// This file is part of pallet-ema-oracle.
+
+// Copyright (C) 2022-2023  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+use frame_support::sp_runtime::DispatchError::BadOrigin;
+
+fn expect_events(events: Vec<TestEvent>) {
+    let mut last_events: Vec<TestEvent> = System::events().into_iter().map(|e| e.event).collect();
+    last_events = last_events.split_off(last_events.len() - events.len());
+    assert_eq!(last_events, events);
+}
+
+#[test]
+fn add_oracle_should_add_entry_to_whitelist() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(EmaOracle::add_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (UNTRACKED_ASSET, HDX)
+        ));
+
+        assert!(WhitelistedAssets::<Test>::contains_key((
+            SOURCE,
+            ordered_pair(HDX, UNTRACKED_ASSET)
+        )));
+        expect_events(vec![Event::<Test>::AddedToWhitelist {
+            source: SOURCE,
+            assets: ordered_pair(HDX, UNTRACKED_ASSET),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn add_oracle_should_fail_when_origin_is_not_authority() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            EmaOracle::add_oracle(RuntimeOrigin::signed(1), SOURCE, (HDX, UNTRACKED_ASSET)),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn add_oracle_should_not_be_limited_by_max_unique_entries() {
+    new_test_ext().execute_with(|| {
+        let max_entries = <<Test as crate::Config>::MaxUniqueEntries as Get<u32>>::get();
+        for i in 0..max_entries {
+            assert_ok!(EmaOracle::add_oracle(RuntimeOrigin::root(), SOURCE, (i, i + 1)));
+        }
+        assert_ok!(EmaOracle::add_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (HDX, UNTRACKED_ASSET)
+        ));
+        assert!(WhitelistedAssets::<Test>::contains_key((
+            SOURCE,
+            ordered_pair(HDX, UNTRACKED_ASSET)
+        )));
+    });
+}
+
+#[test]
+fn on_trade_should_ignore_oracles_that_are_not_whitelisted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE,
+            HDX,
+            UNTRACKED_ASSET,
+            1_000,
+            500,
+            2_000,
+            1_000
+        ));
+        assert_ok!(OnActivityHandler::<Test>::on_liquidity_changed(
+            SOURCE,
+            HDX,
+            UNTRACKED_ASSET,
+            1_000,
+            500,
+            2_000,
+            1_000
+        ));
+        assert_eq!(get_accumulator_entry(SOURCE, (HDX, UNTRACKED_ASSET)), None);
+    });
+}
+
+#[test]
+fn on_trade_should_track_oracles_added_to_whitelist() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        assert_ok!(EmaOracle::add_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (HDX, UNTRACKED_ASSET)
+        ));
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE,
+            HDX,
+            UNTRACKED_ASSET,
+            1_000,
+            500,
+            2_000,
+            1_000
+        ));
+        let expected = OracleEntry {
+            price: Price::new(2_000, 1_000),
+            volume: Volume::from_a_in_b_out(1_000, 500),
+            liquidity: Liquidity::new(2_000, 1_000),
+            timestamp: 5,
+        };
+        assert_eq!(get_accumulator_entry(SOURCE, (HDX, UNTRACKED_ASSET)), Some(expected));
+    });
+}
+
+#[test]
+fn remove_oracle_should_clean_up_oracle_storage() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(5);
+        assert_ok!(EmaOracle::add_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (HDX, UNTRACKED_ASSET)
+        ));
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE,
+            HDX,
+            UNTRACKED_ASSET,
+            1_000,
+            500,
+            2_000,
+            1_000
+        ));
+        EmaOracle::on_finalize(5);
+        for period in supported_periods() {
+            assert!(get_oracle_entry(HDX, UNTRACKED_ASSET, period).is_some());
+        }
+
+        System::set_block_number(6);
+        assert_ok!(OnActivityHandler::<Test>::on_trade(
+            SOURCE,
+            HDX,
+            UNTRACKED_ASSET,
+            1_000,
+            500,
+            2_000,
+            1_000
+        ));
+        assert_ok!(EmaOracle::remove_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (UNTRACKED_ASSET, HDX)
+        ));
+
+        assert!(!WhitelistedAssets::<Test>::contains_key((
+            SOURCE,
+            ordered_pair(HDX, UNTRACKED_ASSET)
+        )));
+        assert_eq!(get_accumulator_entry(SOURCE, (HDX, UNTRACKED_ASSET)), None);
+        for period in supported_periods() {
+            assert_eq!(get_oracle_entry(HDX, UNTRACKED_ASSET, period), None);
+        }
+        expect_events(vec![Event::<Test>::RemovedFromWhitelist {
+            source: SOURCE,
+            assets: ordered_pair(HDX, UNTRACKED_ASSET),
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn remove_oracle_should_fail_when_oracle_is_not_whitelisted() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            EmaOracle::remove_oracle(RuntimeOrigin::root(), SOURCE, (HDX, UNTRACKED_ASSET)),
+            Error::<Test>::OracleNotFound
+        );
+    });
+}
+
+#[test]
+fn remove_oracle_should_fail_when_origin_is_not_authority() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(EmaOracle::add_oracle(
+            RuntimeOrigin::root(),
+            SOURCE,
+            (HDX, UNTRACKED_ASSET)
+        ));
+        assert_noop!(
+            EmaOracle::remove_oracle(RuntimeOrigin::signed(1), SOURCE, (HDX, UNTRACKED_ASSET)),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn genesis_config_should_whitelist_initial_data() {
+    ExtBuilder::default()
+        .with_initial_data(vec![(
+            SOURCE,
+            (UNTRACKED_ASSET, HDX),
+            (1_000_000, 1).into(),
+            Liquidity::new(2_000_000, 2_000_000_000),
+        )])
+        .build()
+        .execute_with(|| {
+            assert!(WhitelistedAssets::<Test>::contains_key((
+                SOURCE,
+                ordered_pair(HDX, UNTRACKED_ASSET)
+            )));
+        });
+}
@@ -17,11 +17,15 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::sp_runtime::RuntimeDebug;
-use hydra_dx_math::ema::{calculate_new_by_integrating_incoming, update_outdated_to_current, EmaPrice};
+use hydra_dx_math::ema::{
+    calculate_new_by_integrating_incoming, smoothing_from_period, update_outdated_to_current, EmaPrice,
+};
 use hydra_dx_math::types::Fraction;
 use hydradx_traits::{AggregatedEntry, Liquidity, Volume};
 use scale_info::TypeInfo;
 use sp_arithmetic::traits::{AtLeast32BitUnsigned, SaturatedConversion, UniqueSaturatedInto};
+use sp_arithmetic::{PerThing, Permill};
+use sp_core::{U256, U512};
 
 pub use hydradx_traits::{OraclePeriod, Source};
 
@@ -69,13 +73,7 @@ where
     /// Return an inverted version of the entry where the meaning of assets a and b are inverted.
     /// So the price of a/b become the price b/a and the volume switches correspondingly.
     pub fn inverted(self) -> Self {
-        // It makes sense for the reciprocal of zero to be zero here.
-        let price = if self.price.is_zero() {
-            self.price
-        } else {
-            let (a, b) = self.price.into();
-            (b, a).into()
-        };
+        let price = invert_price(self.price);
         let volume = self.volume.inverted();
         let liquidity = self.liquidity.inverted();
         Self {
@@ -195,6 +193,9 @@ where
 
 /// Convert a given `period` into the smoothing factor used in the weighted average.
 /// See [`check_period_smoothing_factors`] for how the values are generated.
+///
+/// The smoothing factor of `Custom` periods is derived from their number of blocks. A period of
+/// zero blocks is treated like a period of one block.
 pub fn into_smoothing(period: OraclePeriod) -> Fraction {
     match period {
         OraclePeriod::LastBlock => Fraction::from_bits(170141183460469231731687303715884105728),
@@ -203,6 +204,208 @@ pub fn into_smoothing(period: OraclePeriod) -> Fraction {
         OraclePeriod::Hour => Fraction::from_bits(566193622164623067326746434994622648),
         OraclePeriod::Day => Fraction::from_bits(23629079016800115510268356880200556),
         OraclePeriod::Week => Fraction::from_bits(3375783642235081630771268215908257),
+        OraclePeriod::Custom(blocks) => smoothing_from_period(u64::from(blocks.max(1))),
+    }
+}
+
+/// Multiply two prices, e.g. `A/B * B/C = A/C`.
+///
+/// The result is rounded to fit into `u128` numerator and denominator. Returns `None` if the
+/// resulting price is too large to be represented or a denominator is zero.
+pub fn multiply_prices(a: Price, b: Price) -> Option<Price> {
+    let (a_n, a_d): (u128, u128) = a.into();
+    let (b_n, b_d): (u128, u128) = b.into();
+    // cannot overflow as the factors have at most 128 bits each
+    round_to_price(U256::from(a_n) * U256::from(b_n), U256::from(a_d) * U256::from(b_d))
+}
+
+/// Round the rational number `n / d` to a `Price` by dropping the least significant bits of
+/// numerator and denominator. Returns `None` if the denominator becomes zero.
+fn round_to_price(n: U256, d: U256) -> Option<Price> {
+    let shift = n.bits().max(d.bits()).saturating_sub(128);
+    let (n, d) = (n >> shift, d >> shift);
+    if d.is_zero() {
+        return None;
+    }
+    Some(Price::new(n.low_u128(), d.low_u128()))
+}
+
+/// Return the reciprocal of `price`.
+pub fn invert_price(price: Price) -> Price {
+    // It makes sense for the reciprocal of zero to be zero here.
+    if price.is_zero() {
+        price
+    } else {
+        let (a, b) = price.into();
+        (b, a).into()
+    }
+}
+
+/// Calculate the time weighted arithmetic average price of `observations` over the `window` blocks
+/// ending with block `end` (inclusive).
+///
+/// `observations` are `(block, price)` tuples sorted by block. Each price is considered valid from
+/// its block until the block of the next observation (or `end`).
+///
+/// Returns `None` if the observations do not cover the whole window, i.e. the first observation is
+/// after the start of the window.
+pub fn calculate_twap(observations: &[(u64, Price)], end: u64, window: u64) -> Option<Price> {
+    let start = end.saturating_add(1).saturating_sub(window);
+    match observations.first() {
+        Some((first_block, _)) if *first_block <= start => {}
+        _ => return None,
+    }
+    let mut weighted_sum = U512::zero();
+    let mut total_weight: u64 = 0;
+    for (i, (block, price)) in observations.iter().enumerate() {
+        let segment_start = (*block).max(start);
+        let segment_end = observations
+            .get(i + 1)
+            .map(|(next_block, _)| *next_block)
+            .unwrap_or_else(|| end.saturating_add(1))
+            .min(end.saturating_add(1));
+        if segment_end <= segment_start {
+            continue;
+        }
+        let value = match to_fixed(*price) {
+            Some(value) => value,
+            None => continue,
+        };
+        let weight = segment_end - segment_start;
+        weighted_sum = weighted_sum.saturating_add(U512::from(value) * U512::from(weight));
+        total_weight = total_weight.saturating_add(weight);
+    }
+    if total_weight == 0 {
+        return None;
+    }
+    let average = U256::try_from(weighted_sum / U512::from(total_weight)).ok()?;
+    from_fixed(average)
+}
+
+/// Convert `price` to a fixed point number with 128 fractional bits. Returns `None` if the
+/// denominator is zero.
+fn to_fixed(price: Price) -> Option<U256> {
+    let (n, d): (u128, u128) = price.into();
+    if d == 0 {
+        return None;
+    }
+    Some((U256::from(n) << 128) / U256::from(d))
+}
+
+/// Convert a fixed point number with 128 fractional bits back to a `Price`.
+fn from_fixed(value: U256) -> Option<Price> {
+    round_to_price(value, U256::one() << 128)
+}
+
+/// How the prices of multiple sources for the same asset pair are combined into one price.
+#[derive(RuntimeDebug, Encode, Decode, Copy, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum AggregationMode {
+    /// Average of the prices weighted by the liquidity of the first asset of the (ordered) pair in
+    /// each source.
+    LiquidityWeightedAverage,
+    /// Median of the prices. The mean of the two middle prices for an even number of prices.
+    Median,
+}
+
+/// Contribution of a single source to a price aggregated across sources.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct SourceContribution<BlockNumber> {
+    pub source: Source,
+    pub price: Price,
+    /// Liquidity of the first asset of the pair in this source.
+    pub liquidity: Balance,
+    pub oracle_age: BlockNumber,
+}
+
+/// Combine `prices` given as `(price, liquidity)` tuples according to `mode`.
+///
+/// The liquidity weighted average falls back to equal weights if the total liquidity is zero.
+/// Returns `None` if no price is given or the result cannot be represented.
+pub fn aggregate_prices(mode: AggregationMode, prices: &[(Price, Balance)]) -> Option<Price> {
+    let values: Vec<(U256, Balance)> = prices
+        .iter()
+        .filter_map(|(price, liquidity)| to_fixed(*price).map(|value| (value, *liquidity)))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    match mode {
+        AggregationMode::LiquidityWeightedAverage => {
+            let total_liquidity = values.iter().fold(U512::zero(), |acc, (_, liquidity)| {
+                acc.saturating_add(U512::from(*liquidity))
+            });
+            let (weighted_sum, total_weight) = if total_liquidity.is_zero() {
+                let sum = values
+                    .iter()
+                    .fold(U512::zero(), |acc, (value, _)| acc.saturating_add(U512::from(*value)));
+                (sum, U512::from(values.len() as u64))
+            } else {
+                let sum = values.iter().fold(U512::zero(), |acc, (value, liquidity)| {
+                    acc.saturating_add(U512::from(*value).saturating_mul(U512::from(*liquidity)))
+                });
+                (sum, total_liquidity)
+            };
+            from_fixed(U256::try_from(weighted_sum / total_weight).ok()?)
+        }
+        AggregationMode::Median => {
+            let mut sorted: Vec<U256> = values.into_iter().map(|(value, _)| value).collect();
+            sorted.sort();
+            let mid = sorted.len() / 2;
+            let median = if sorted.len() % 2 == 0 {
+                // cannot overflow as both values are at most 256 bits
+                U256::try_from((U512::from(sorted[mid - 1]) + U512::from(sorted[mid])) / 2).ok()?
+            } else {
+                sorted[mid]
+            };
+            from_fixed(median)
+        }
+    }
+}
+
+/// What to do with an oracle update whose price deviates more than allowed from the previous
+/// `LastBlock` price.
+#[derive(RuntimeDebug, Encode, Decode, Copy, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum PriceLimitAction {
+    /// Clamp the incoming price to the maximum allowed change.
+    Clamp,
+    /// Ignore the update for all periods, at most `MaxIgnoredUpdates` consecutive times.
+    Ignore,
+}
+
+/// Limit on how much the incoming price may deviate from the previous `LastBlock` price.
+#[derive(RuntimeDebug, Encode, Decode, Copy, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct PriceChangeLimit {
+    /// Maximum change relative to the previous price.
+    pub max_change: Permill,
+    /// Action taken when the limit is exceeded.
+    pub action: PriceLimitAction,
+}
+
+/// Check whether `incoming` deviates from `previous` by more than `max_change` (relative to
+/// `previous`).
+///
+/// Returns `None` if the price is within the limit, otherwise the closest price within the limit.
+/// A zero `previous` price does not impose a limit.
+pub fn limit_price_change(previous: Price, incoming: Price, max_change: Permill) -> Option<Price> {
+    let (prev_n, prev_d): (u128, u128) = previous.into();
+    let (in_n, in_d): (u128, u128) = incoming.into();
+    if prev_n == 0 || prev_d == 0 || in_d == 0 {
+        return None;
+    }
+    let accuracy = U256::from(Permill::ACCURACY);
+    let change = U256::from(max_change.deconstruct());
+    let upper_n = U256::from(prev_n) * (accuracy + change);
+    let lower_n = U256::from(prev_n) * (accuracy - change);
+    let bound_d = U256::from(prev_d) * accuracy;
+
+    // compare `in_n / in_d` with `bound_n / bound_d` by cross multiplication
+    let incoming_scaled = U512::from(in_n) * U512::from(bound_d);
+    if incoming_scaled > U512::from(in_d) * U512::from(upper_n) {
+        round_to_price(upper_n, bound_d)
+    } else if incoming_scaled < U512::from(in_d) * U512::from(lower_n) {
+        round_to_price(lower_n, bound_d)
+    } else {
+        None
     }
 }
 
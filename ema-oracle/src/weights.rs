@@ -35,6 +35,10 @@
 // --pallet=pallet_ema_oracle
 // --output=oracle.rs
 // --extrinsic=*
+//
+// NOTE: The weights of `add_oracle`, `remove_oracle`, `set_price_change_limit`,
+// `set_custom_periods` and `remove_stale_oracles` are placeholders that have not been benchmarked
+// yet. They are meant to be replaced by re-running the command above.
 #![allow(unused_parens)]
 #![allow(unused_imports)]
 #![allow(clippy::unnecessary_cast)]
@@ -52,6 +56,11 @@ pub trait WeightInfo {
     fn on_trade_multiple_tokens(b: u32) -> Weight;
     fn on_liquidity_changed_multiple_tokens(b: u32) -> Weight;
     fn get_entry() -> Weight;
+    fn add_oracle() -> Weight;
+    fn remove_oracle(p: u32) -> Weight;
+    fn set_price_change_limit() -> Weight;
+    fn set_custom_periods() -> Weight;
+    fn remove_stale_oracles(n: u32) -> Weight;
 }
 
 pub struct BasiliskWeight<T>(PhantomData<T>);
@@ -83,6 +92,37 @@ impl<T: frame_system::Config> WeightInfo for BasiliskWeight<T> {
     fn get_entry() -> Weight {
         Weight::from_ref_time(23_575_000 as u64).saturating_add(T::DbWeight::get().reads(2 as u64))
     }
+    fn add_oracle() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(17_882_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn remove_oracle(p: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(30_521_000 as u64)
+            .saturating_add(Weight::from_ref_time(1_750_000 as u64).saturating_mul(p as u64))
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
+            .saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(p as u64)))
+    }
+    fn set_price_change_limit() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(14_316_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn set_custom_periods() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(17_024_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
+    fn remove_stale_oracles(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(9_236_000 as u64)
+            .saturating_add(Weight::from_ref_time(11_585_000 as u64).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+    }
 }
 
 // For backwards compatibility and tests
@@ -113,4 +153,35 @@ impl WeightInfo for () {
     fn get_entry() -> Weight {
         Weight::from_ref_time(23_575_000 as u64).saturating_add(RocksDbWeight::get().reads(2 as u64))
     }
+    fn add_oracle() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(17_882_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn remove_oracle(p: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(30_521_000 as u64)
+            .saturating_add(Weight::from_ref_time(1_750_000 as u64).saturating_mul(p as u64))
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+            .saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(p as u64)))
+    }
+    fn set_price_change_limit() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(14_316_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn set_custom_periods() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(17_024_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
+    fn remove_stale_oracles(n: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(9_236_000 as u64)
+            .saturating_add(Weight::from_ref_time(11_585_000 as u64).saturating_mul(n as u64))
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+    }
 }
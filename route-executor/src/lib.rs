@@ -22,12 +22,15 @@ use frame_support::ensure;
 use frame_support::traits::fungibles::Inspect;
 use frame_support::traits::Get;
 use frame_support::transactional;
+use frame_support::{dispatch::DispatchResult, weights::Weight};
 use frame_system::ensure_signed;
 use hydradx_traits::router::TradeExecution;
-use hydradx_traits::router::{ExecutorError, PoolType};
+use hydradx_traits::router::{ExecutorError, PoolType, RouterSell};
 use orml_traits::arithmetic::{CheckedAdd, CheckedSub};
+use orml_traits::GetByKey;
 use scale_info::TypeInfo;
 use sp_runtime::DispatchError;
+use sp_std::marker::PhantomData;
 use sp_std::vec::Vec;
 
 #[cfg(test)]
@@ -381,6 +384,40 @@ impl<T: Config> Pallet<T> {
     }
 }
 
+/// Sells along the route provided by `Routes` for the asset pair.
+///
+/// Asset pairs without a route fail with `RouteHasNoTrades`.
+pub struct SellThroughRoute<T, Routes>(PhantomData<(T, Routes)>);
+
+impl<T, Routes> RouterSell<T::AccountId, T::AssetId, T::Balance> for SellThroughRoute<T, Routes>
+where
+    T: Config,
+    Routes: GetByKey<(T::AssetId, T::AssetId), Vec<Trade<T::AssetId>>>,
+{
+    fn sell(
+        who: &T::AccountId,
+        asset_in: T::AssetId,
+        asset_out: T::AssetId,
+        amount_in: T::Balance,
+        min_amount_out: T::Balance,
+    ) -> DispatchResult {
+        let route = Routes::get(&(asset_in, asset_out));
+
+        Pallet::<T>::sell(
+            frame_system::RawOrigin::Signed(who.clone()).into(),
+            asset_in,
+            asset_out,
+            amount_in,
+            min_amount_out,
+            route,
+        )
+    }
+
+    fn sell_weight() -> Weight {
+        T::WeightInfo::sell(T::MaxNumberOfTrades::get() as u32)
+    }
+}
+
 #[macro_export]
 macro_rules! handle_execution_error {
     ($execution_result:expr) => {{
@@ -16,9 +16,10 @@
 // limitations under the License.
 
 use crate::tests::mock::*;
-use crate::{Error, Event, Trade};
+use crate::{Error, Event, SellThroughRoute, Trade};
 use frame_support::{assert_noop, assert_ok};
-use hydradx_traits::router::PoolType;
+use hydradx_traits::router::{PoolType, RouterSell};
+use orml_traits::GetByKey;
 use pretty_assertions::assert_eq;
 use sp_runtime::DispatchError;
 use sp_runtime::DispatchError::BadOrigin;
@@ -344,3 +345,53 @@ fn sell_should_fail_when_min_limit_to_receive_is_not_reached() {
         );
     });
 }
+
+pub struct TestRoutes;
+
+impl GetByKey<(AssetId, AssetId), Vec<Trade<AssetId>>> for TestRoutes {
+    fn get(pair: &(AssetId, AssetId)) -> Vec<Trade<AssetId>> {
+        match *pair {
+            (BSX, AUSD) => vec![BSX_AUSD_TRADE_IN_XYK],
+            _ => vec![],
+        }
+    }
+}
+
+#[test]
+fn sell_through_route_should_use_route_of_asset_pair() {
+    ExtBuilder::default().build().execute_with(|| {
+        //Arrange
+        let amount_to_sell = 10;
+        let limit = 5;
+
+        //Act
+        assert_ok!(SellThroughRoute::<Test, TestRoutes>::sell(
+            &ALICE,
+            BSX,
+            AUSD,
+            amount_to_sell,
+            limit
+        ));
+
+        //Assert
+        assert_executed_sell_trades(vec![(PoolType::XYK, amount_to_sell, BSX, AUSD)]);
+        expect_events(vec![Event::RouteExecuted {
+            asset_in: BSX,
+            asset_out: AUSD,
+            amount_in: amount_to_sell,
+            amount_out: XYK_SELL_CALCULATION_RESULT,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn sell_through_route_should_fail_when_asset_pair_has_no_route() {
+    ExtBuilder::default().build().execute_with(|| {
+        //Act and Assert
+        assert_noop!(
+            SellThroughRoute::<Test, TestRoutes>::sell(&ALICE, BSX, KSM, 10, 5),
+            Error::<Test>::RouteHasNoTrades
+        );
+    });
+}
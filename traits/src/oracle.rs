@@ -2,7 +2,9 @@ use super::*;
 
 use codec::MaxEncodedLen;
 use frame_support::sp_runtime::traits::{AtLeast32BitUnsigned, One};
+use frame_support::traits::Get;
 use scale_info::TypeInfo;
+use sp_arithmetic::FixedPointNumber;
 
 /// Implementers of this trait provide the price of a given asset compared to the native currency.
 ///
@@ -42,6 +44,7 @@ where
 ///
 /// Note: Some of the oracles are named after certain periods of time.
 /// This description relies on the mapping of the enum to the internal implementation and can thus not be guaranteed.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub enum OraclePeriod {
     /// The oracle data is from the last block, thus unaggregated.
@@ -56,6 +59,10 @@ pub enum OraclePeriod {
     Day,
     /// The oracle data was aggregated over the blocks of the last week.
     Week,
+    /// The oracle data was aggregated over the given number of blocks.
+    ///
+    /// Appended after the predefined periods so their encoding stays the same.
+    Custom(u32),
 }
 
 impl OraclePeriod {
@@ -321,3 +328,194 @@ where
         Weight::zero()
     }
 }
+
+/// Price of an oracle together with the information needed to judge its freshness.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+pub struct OraclePriceInfo<Price, BlockNumber> {
+    pub price: Price,
+    /// Number of blocks since the oracle was initialized.
+    pub oracle_age: BlockNumber,
+    /// Number of blocks since the oracle last received data.
+    pub blocks_since_update: BlockNumber,
+}
+
+/// An oracle returning a price aggregated over `period` together with the information needed to
+/// judge its freshness.
+pub trait AggregatedPriceInfoOracle<AssetId, BlockNumber, Price> {
+    type Error;
+    fn get_price_info(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<OraclePriceInfo<Price, BlockNumber>, Self::Error>;
+
+    fn get_price_info_weight() -> Weight;
+}
+
+/// Errors returned by [`FreshPriceOracle`].
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+pub enum FreshnessError<E> {
+    /// The oracle was initialized less than the required minimum age ago and did not have a chance
+    /// to settle yet.
+    NotReady,
+    /// The oracle did not receive data for longer than the allowed maximum staleness.
+    Stale,
+    /// The underlying oracle returned an error.
+    Oracle(E),
+}
+
+/// Wrapper around an [`AggregatedPriceInfoOracle`] that only returns prices of oracles that are at
+/// least `MinOracleAge` blocks old and received data within the last `MaxStaleness` blocks.
+///
+/// Returns the price together with the oracle age like any other `AggregatedPriceOracle`.
+pub struct FreshPriceOracle<Oracle, MaxStaleness, MinOracleAge>(
+    sp_std::marker::PhantomData<(Oracle, MaxStaleness, MinOracleAge)>,
+);
+
+impl<AssetId, BlockNumber, Price, Oracle, MaxStaleness, MinOracleAge> AggregatedPriceOracle<AssetId, BlockNumber, Price>
+    for FreshPriceOracle<Oracle, MaxStaleness, MinOracleAge>
+where
+    BlockNumber: PartialOrd,
+    Oracle: AggregatedPriceInfoOracle<AssetId, BlockNumber, Price>,
+    MaxStaleness: Get<BlockNumber>,
+    MinOracleAge: Get<BlockNumber>,
+{
+    type Error = FreshnessError<Oracle::Error>;
+
+    fn get_price(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<(Price, BlockNumber), Self::Error> {
+        let OraclePriceInfo {
+            price,
+            oracle_age,
+            blocks_since_update,
+        } = Oracle::get_price_info(asset_a, asset_b, period, source).map_err(FreshnessError::Oracle)?;
+        if oracle_age < MinOracleAge::get() {
+            return Err(FreshnessError::NotReady);
+        }
+        if blocks_since_update > MaxStaleness::get() {
+            return Err(FreshnessError::Stale);
+        }
+        Ok((price, oracle_age))
+    }
+
+    fn get_price_weight() -> Weight {
+        Oracle::get_price_info_weight()
+    }
+}
+
+/// Errors returned by [`PriceInfoOracleAdapter`].
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+pub enum PriceConversionError<E> {
+    /// The price of the underlying oracle cannot be represented as the target price type.
+    Conversion,
+    /// The underlying oracle returned an error.
+    Oracle(E),
+}
+
+/// Adapter exposing an [`AggregatedPriceInfoOracle`] with rational prices and its own asset ids,
+/// e.g. the EMA oracle, as an oracle with fixed point prices.
+///
+/// Asset ids are converted to `InnerAssetId` and the rational price `(n, d)` to the fixed point
+/// number `n / d`.
+pub struct PriceInfoOracleAdapter<Oracle, InnerAssetId, InnerPrice>(
+    sp_std::marker::PhantomData<(Oracle, InnerAssetId, InnerPrice)>,
+);
+
+impl<AssetId, BlockNumber, Price, Oracle, InnerAssetId, InnerPrice>
+    AggregatedPriceInfoOracle<AssetId, BlockNumber, Price> for PriceInfoOracleAdapter<Oracle, InnerAssetId, InnerPrice>
+where
+    AssetId: Into<InnerAssetId>,
+    Price: FixedPointNumber,
+    InnerPrice: Into<(u128, u128)>,
+    Oracle: AggregatedPriceInfoOracle<InnerAssetId, BlockNumber, InnerPrice>,
+{
+    type Error = PriceConversionError<Oracle::Error>;
+
+    fn get_price_info(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<OraclePriceInfo<Price, BlockNumber>, Self::Error> {
+        let OraclePriceInfo {
+            price,
+            oracle_age,
+            blocks_since_update,
+        } = Oracle::get_price_info(asset_a.into(), asset_b.into(), period, source)
+            .map_err(PriceConversionError::Oracle)?;
+        let (n, d) = price.into();
+        let price = Price::checked_from_rational(n, d).ok_or(PriceConversionError::Conversion)?;
+        Ok(OraclePriceInfo {
+            price,
+            oracle_age,
+            blocks_since_update,
+        })
+    }
+
+    fn get_price_info_weight() -> Weight {
+        Oracle::get_price_info_weight()
+    }
+}
+
+/// A single step of an oracle route: the oracle of `source` for the pair `asset_in/asset_out`.
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct OracleRouteHop<AssetId> {
+    pub source: Source,
+    pub asset_in: AssetId,
+    pub asset_out: AssetId,
+}
+
+/// Implementers of this trait provide the route of oracles connecting two assets. Used to derive
+/// prices for asset pairs that do not have a direct oracle.
+pub trait OracleRouteProvider<AssetId> {
+    /// Return the route of oracles from `asset_a` to `asset_b` using oracles of `source` where
+    /// applicable.
+    ///
+    /// Should return `None` if no route is available.
+    fn route(source: Source, asset_a: AssetId, asset_b: AssetId) -> Option<Vec<OracleRouteHop<AssetId>>>;
+
+    /// The maximum number of hops of any route returned by `route`.
+    fn max_hops() -> u32;
+}
+
+/// Route provider that routes every asset pair through the `Hub` asset, e.g. `A/LRNA * LRNA/B`.
+/// All oracles along the route are taken from the same source.
+pub struct RouteThroughAsset<Hub>(sp_std::marker::PhantomData<Hub>);
+
+impl<AssetId, Hub> OracleRouteProvider<AssetId> for RouteThroughAsset<Hub>
+where
+    AssetId: Copy + PartialEq,
+    Hub: Get<AssetId>,
+{
+    fn route(source: Source, asset_a: AssetId, asset_b: AssetId) -> Option<Vec<OracleRouteHop<AssetId>>> {
+        let hub = Hub::get();
+        if asset_a == hub || asset_b == hub {
+            return Some(sp_std::vec![OracleRouteHop {
+                source,
+                asset_in: asset_a,
+                asset_out: asset_b,
+            }]);
+        }
+        Some(sp_std::vec![
+            OracleRouteHop {
+                source,
+                asset_in: asset_a,
+                asset_out: hub,
+            },
+            OracleRouteHop {
+                source,
+                asset_in: hub,
+                asset_out: asset_b,
+            },
+        ])
+    }
+
+    fn max_hops() -> u32 {
+        2
+    }
+}
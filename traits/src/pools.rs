@@ -22,3 +22,14 @@ pub trait DustRemovalAccountWhitelist<AccountId> {
     /// Remove an account from the list.
     fn remove_account(account: &AccountId) -> Result<(), Self::Error>;
 }
+
+/// Manage list of accounts which are non-dustable for a single currency.
+pub trait DustRemovalCurrencyWhitelist<AccountId, CurrencyId> {
+    type Error;
+
+    /// Add account to the list of `currency_id`.
+    fn add_account_currency(account: &AccountId, currency_id: CurrencyId) -> Result<(), Self::Error>;
+
+    /// Remove an account from the list of `currency_id`.
+    fn remove_account_currency(account: &AccountId, currency_id: CurrencyId) -> Result<(), Self::Error>;
+}
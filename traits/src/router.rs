@@ -1,4 +1,6 @@
 use codec::{Decode, Encode};
+use frame_support::dispatch::DispatchResult;
+use frame_support::weights::Weight;
 use scale_info::TypeInfo;
 
 #[derive(Encode, Decode, Clone, Copy, Debug, Eq, PartialEq, TypeInfo)]
@@ -15,6 +17,21 @@ pub enum ExecutorError<E> {
     Error(E),
 }
 
+/// Implementers of this trait sell assets on behalf of an account along a route they determine themselves.
+pub trait RouterSell<AccountId, AssetId, Balance> {
+    /// Sell `amount_in` of `asset_in` owned by `who` for at least `min_amount_out` of `asset_out`.
+    fn sell(
+        who: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> DispatchResult;
+
+    /// Maximum weight of `sell`.
+    fn sell_weight() -> Weight;
+}
+
 pub trait TradeExecution<Origin, AccountId, AssetId, Balance> {
     type Error;
 
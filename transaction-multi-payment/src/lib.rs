@@ -18,6 +18,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
+pub mod migration;
 pub mod weights;
 
 use weights::WeightInfo;
@@ -29,22 +30,33 @@ mod mock;
 mod tests;
 mod traits;
 
-use frame_support::{dispatch::DispatchResult, ensure, traits::Get, weights::Weight};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+    dispatch::{DispatchClass, DispatchInfo, DispatchResult, PostDispatchInfo},
+    ensure,
+    traits::Get,
+    weights::Weight,
+    BoundedVec,
+};
 use frame_system::ensure_signed;
+use scale_info::TypeInfo;
 use sp_runtime::{
-    traits::{DispatchInfoOf, One, PostDispatchInfoOf, Saturating, Zero},
-    transaction_validity::{InvalidTransaction, TransactionValidityError},
-    FixedU128,
+    traits::{DispatchInfoOf, Dispatchable, One, PostDispatchInfoOf, Saturating, SignedExtension, Zero},
+    transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction},
+    FixedU128, Permill, RuntimeDebug, SaturatedConversion,
 };
 use sp_std::prelude::*;
 
-use pallet_transaction_payment::OnChargeTransaction;
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
 use sp_std::marker::PhantomData;
 
 use frame_support::sp_runtime::FixedPointNumber;
 use frame_support::sp_runtime::FixedPointOperand;
-use hydradx_traits::{pools::SpotPriceProvider, NativePriceOracle};
-use orml_traits::{Happened, MultiCurrency};
+use hydradx_traits::{
+    pools::SpotPriceProvider, router::RouterSell, AggregatedPriceInfoOracle, AggregatedPriceOracle, FreshPriceOracle,
+    NativePriceOracle, OraclePeriod, Source,
+};
+use orml_traits::{Happened, MultiCurrency, MultiReservableCurrency};
 
 use frame_support::traits::IsSubType;
 
@@ -59,6 +71,38 @@ pub type Price = FixedU128;
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
+/// Call identified by the index of its pallet and optionally its call index. Matches all calls of the pallet if
+/// `call_index` is `None`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SponsoredCall {
+    pub pallet_index: u8,
+    pub call_index: Option<u8>,
+}
+
+/// Fees collected in a non-native currency waiting to be swapped to native asset.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct BufferedFee<Balance> {
+    /// Amount of buffered fees
+    pub amount: Balance,
+    /// Number of failed attempts to swap the buffered fees
+    pub failed_swaps: u32,
+}
+
+/// Rule under which a sponsor pays transaction fees of other accounts.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SponsorshipRule<Callers, Calls, Balance, BlockNumber> {
+    /// Accounts whose fees are paid. Fees of all accounts are paid if `None`.
+    pub callers: Option<Callers>,
+    /// Calls whose fees are paid. A call is sponsored by at most one sponsor.
+    pub calls: Calls,
+    /// Maximum fee of a single transaction in native currency.
+    pub max_fee: Balance,
+    /// Maximum sum of fees in native currency paid within a period.
+    pub budget: Balance,
+    /// Length of the budget period in blocks. The budget is never renewed if zero.
+    pub period: BlockNumber,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -66,32 +110,42 @@ pub mod pallet {
     use frame_support::weights::WeightToFee;
     use frame_system::pallet_prelude::OriginFor;
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    /// Maximum length of a raw storage key of `BufferedFees` stored in `FeeSwapCursor`.
+    const MAX_BUFFERED_FEE_KEY_LEN: u32 = 128;
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
+    pub type SponsorshipRuleOf<T> = SponsorshipRule<
+        BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::MaxSponsoredCallers>,
+        BoundedVec<SponsoredCall, <T as Config>::MaxSponsoredCalls>,
+        BalanceOf<T>,
+        <T as frame_system::Config>::BlockNumber,
+    >;
+
     #[pallet::hooks]
     impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
         fn on_initialize(_n: T::BlockNumber) -> Weight {
-            let native_asset = T::NativeAssetId::get();
-
-            let mut weight: u64 = 0;
-
-            for (asset_id, fallback_price) in <AcceptedCurrencies<T>>::iter() {
-                let maybe_price = T::SpotPriceProvider::spot_price(asset_id, native_asset);
-
-                let price = maybe_price.unwrap_or(fallback_price);
+            // prices are cached lazily when used and their weight is registered when fetched, only reading the
+            // cache limit in `on_finalize` is left
+            T::DbWeight::get().reads(1)
+        }
 
-                AcceptedCurrencyPrice::<T>::insert(asset_id, price);
+        fn on_finalize(_n: T::BlockNumber) {
+            let _ = <AcceptedCurrencyPrice<T>>::clear(Self::price_cache_limit(), None);
+        }
 
-                weight += T::WeightInfo::get_spot_price().ref_time();
+        fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let period = T::FeeSwapPeriod::get();
+            if !period.is_zero() && !(n % period).is_zero() {
+                return Weight::zero();
             }
 
-            Weight::from_ref_time(weight)
-        }
-
-        fn on_finalize(_n: T::BlockNumber) {
-            let _ = <AcceptedCurrencyPrice<T>>::clear(u32::MAX, None);
+            Self::swap_buffered_fees(remaining_weight)
         }
     }
 
@@ -104,11 +158,77 @@ pub mod pallet {
         type AcceptedCurrencyOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
         /// Multi Currency
-        type Currencies: MultiCurrency<Self::AccountId>;
+        type Currencies: MultiReservableCurrency<Self::AccountId>;
 
         /// Spot price provider
         type SpotPriceProvider: SpotPriceProvider<AssetIdOf<Self>, Price = Price>;
 
+        /// Oracle providing prices of accepted currencies denominated in native currency.
+        ///
+        /// Oracles with rational prices, e.g. the EMA oracle, can be used via `PriceInfoOracleAdapter`.
+        type Oracle: AggregatedPriceInfoOracle<AssetIdOf<Self>, Self::BlockNumber, Price>;
+
+        /// Source of the oracle prices
+        #[pallet::constant]
+        type OracleSource: Get<Source>;
+
+        /// Aggregation period of the oracle prices
+        #[pallet::constant]
+        type OraclePricePeriod: Get<OraclePeriod>;
+
+        /// Oracle prices which were not updated for more than this number of blocks are not used
+        #[pallet::constant]
+        type MaxOracleStaleness: Get<Self::BlockNumber>;
+
+        /// Oracle prices of oracles initialized less than this number of blocks ago are not used.
+        ///
+        /// Should be non-zero, so that a price set by a single trade in a freshly initialized oracle is not used.
+        #[pallet::constant]
+        type MinOracleAge: Get<Self::BlockNumber>;
+
+        /// Use spot price if the oracle price is not available
+        #[pallet::constant]
+        type SpotPriceFallback: Get<bool>;
+
+        /// Router used to swap fees buffered by `DepositAndSwapFees` to native asset
+        type FeeSwap: RouterSell<Self::AccountId, AssetIdOf<Self>, BalanceOf<Self>>;
+
+        /// Number of blocks between swaps of buffered fees
+        #[pallet::constant]
+        type FeeSwapPeriod: Get<Self::BlockNumber>;
+
+        /// Maximum shortfall of a fee swap compared to the amount expected by the oracle price
+        #[pallet::constant]
+        type MaxFeeSwapSlippage: Get<Permill>;
+
+        /// Number of failed swaps after which buffered fees are removed from the buffer and stay unswapped
+        #[pallet::constant]
+        type MaxFeeSwapAttempts: Get<u32>;
+
+        /// Amount of native currency reserved from a sponsor for each call of its sponsorship rule while the rule is
+        /// set.
+        ///
+        /// Should be high enough to make it expensive to occupy calls, since a call is sponsored by at most one
+        /// sponsor.
+        #[pallet::constant]
+        type SponsorshipDeposit: Get<BalanceOf<Self>>;
+
+        /// Maximum number of callers in a sponsorship rule
+        #[pallet::constant]
+        type MaxSponsoredCallers: Get<u32>;
+
+        /// Maximum number of calls in a sponsorship rule
+        #[pallet::constant]
+        type MaxSponsoredCalls: Get<u32>;
+
+        /// Maximum number of accepted currencies
+        #[pallet::constant]
+        type MaxAcceptedCurrencies: Get<u32>;
+
+        /// Maximum number of currencies in the fee currency fallback order
+        #[pallet::constant]
+        type MaxFallbackCurrencies: Get<u32>;
+
         /// Weight information for the extrinsics.
         type WeightInfo: WeightInfo;
 
@@ -147,6 +267,58 @@ pub mod pallet {
             non_native_fee_amount: BalanceOf<T>,
             destination_account_id: T::AccountId,
         },
+
+        /// Buffered fees swapped to native asset
+        FeesSwapped {
+            account_id: T::AccountId,
+            asset_id: AssetIdOf<T>,
+            amount_in: BalanceOf<T>,
+            amount_out: BalanceOf<T>,
+        },
+
+        /// Buffered fees could not be swapped to native asset and stay buffered for the next swap period
+        FeeSwapFailed {
+            account_id: T::AccountId,
+            asset_id: AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        },
+
+        /// Buffered fees could not be swapped to native asset in `MaxFeeSwapAttempts` attempts and were removed from
+        /// the buffer. The fees stay in the account of the fee receiver.
+        FeeSwapAbandoned {
+            account_id: T::AccountId,
+            asset_id: AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        },
+
+        /// Sponsorship rule set
+        SponsorshipSet {
+            sponsor: T::AccountId,
+            rule: SponsorshipRuleOf<T>,
+        },
+
+        /// Sponsorship rule removed
+        SponsorshipRemoved { sponsor: T::AccountId },
+
+        /// Fee currency fallback order set or disabled
+        FallbackCurrenciesSet {
+            currencies: Option<BoundedVec<AssetIdOf<T>, T::MaxFallbackCurrencies>>,
+        },
+
+        /// Transaction fee paid in a fallback currency because the requested currency could not be used
+        FeeCurrencyFallback {
+            account_id: T::AccountId,
+            requested_asset_id: AssetIdOf<T>,
+            used_asset_id: AssetIdOf<T>,
+        },
+
+        /// Transaction fee paid by sponsor
+        /// [Sponsor, Account, Native fee amount]
+        FeeSponsored {
+            sponsor: T::AccountId,
+            account_id: T::AccountId,
+            native_fee_amount: BalanceOf<T>,
+        },
     }
 
     #[pallet::error]
@@ -171,6 +343,21 @@ pub mod pallet {
 
         /// Math overflow
         Overflow,
+
+        /// Sponsorship rule contains no calls.
+        NoSponsoredCalls,
+
+        /// Call is already sponsored by another sponsor.
+        CallAlreadySponsored,
+
+        /// Account has no sponsorship rule.
+        SponsorshipNotFound,
+
+        /// Number of fallback currencies exceeds `MaxFallbackCurrencies`.
+        TooManyFallbackCurrencies,
+
+        /// Number of accepted currencies exceeds `MaxAcceptedCurrencies`.
+        TooManyAcceptedCurrencies,
     }
 
     /// Account currency map
@@ -183,11 +370,68 @@ pub mod pallet {
     #[pallet::getter(fn currencies)]
     pub type AcceptedCurrencies<T: Config> = StorageMap<_, Twox64Concat, AssetIdOf<T>, Price, OptionQuery>;
 
-    /// Asset prices from the spot price provider or the fallback price if the price is not available. Updated at the beginning of every block.
+    /// Number of entries in `AcceptedCurrencies`
+    #[pallet::storage]
+    pub type AcceptedCurrencyCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Asset prices from the oracle, the spot price provider or the fallback price if the price is not available.
+    /// Cached when the currency is first used in a block and cleared at the end of the block.
     #[pallet::storage]
     #[pallet::getter(fn currency_price)]
     pub type AcceptedCurrencyPrice<T: Config> = StorageMap<_, Twox64Concat, AssetIdOf<T>, Price, OptionQuery>;
 
+    /// Fees collected in non-native currencies waiting to be swapped to native asset, keyed by fee receiver and currency.
+    #[pallet::storage]
+    #[pallet::getter(fn buffered_fees)]
+    pub type BufferedFees<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetIdOf<T>,
+        BufferedFee<BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// Raw storage key of the last entry of `BufferedFees` handled in the previous swap period. The next swap period
+    /// continues after it. Swaps start from the first entry if not set.
+    #[pallet::storage]
+    pub type FeeSwapCursor<T: Config> =
+        StorageValue<_, BoundedVec<u8, ConstU32<MAX_BUFFERED_FEE_KEY_LEN>>, OptionQuery>;
+
+    /// Currencies tried in this order after the native currency if the fee cannot be paid in the requested currency.
+    /// Fallback is disabled if not set.
+    #[pallet::storage]
+    #[pallet::getter(fn fallback_currencies)]
+    pub type FallbackCurrencies<T: Config> =
+        StorageValue<_, BoundedVec<AssetIdOf<T>, T::MaxFallbackCurrencies>, OptionQuery>;
+
+    /// Sponsorship rules of sponsors
+    #[pallet::storage]
+    #[pallet::getter(fn sponsorship)]
+    pub type SponsorshipRules<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SponsorshipRuleOf<T>, OptionQuery>;
+
+    /// Number of entries in `SponsorshipRules`
+    #[pallet::storage]
+    pub type SponsorCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// Sponsors of calls in sponsorship rules
+    #[pallet::storage]
+    #[pallet::getter(fn call_sponsor)]
+    pub type CallSponsors<T: Config> = StorageMap<_, Twox64Concat, SponsoredCall, T::AccountId, OptionQuery>;
+
+    /// Start of the current budget period of a sponsor and the fees paid within it
+    #[pallet::storage]
+    #[pallet::getter(fn sponsorship_spending)]
+    pub type SponsorshipSpending<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, BalanceOf<T>), ValueQuery>;
+
+    /// Native currency reserved from sponsors for their sponsorship rules
+    #[pallet::storage]
+    #[pallet::getter(fn sponsorship_deposit)]
+    pub type SponsorshipDeposits<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub currencies: Vec<(AssetIdOf<T>, Price)>,
@@ -207,9 +451,15 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
+            assert!(
+                self.currencies.len() <= T::MaxAcceptedCurrencies::get() as usize,
+                "Number of accepted currencies exceeds MaxAcceptedCurrencies"
+            );
+
             for (asset, price) in &self.currencies {
                 AcceptedCurrencies::<T>::insert(asset, price);
             }
+            AcceptedCurrencyCount::<T>::put(AcceptedCurrencies::<T>::iter_keys().count() as u32);
 
             for (account, asset) in &self.account_currencies {
                 <AccountCurrencyMap<T>>::insert(account, asset);
@@ -270,6 +520,15 @@ pub mod pallet {
                     return Err(Error::<T>::AlreadyAccepted.into());
                 }
 
+                AcceptedCurrencyCount::<T>::try_mutate(|count| -> DispatchResult {
+                    ensure!(
+                        *count < T::MaxAcceptedCurrencies::get(),
+                        Error::<T>::TooManyAcceptedCurrencies
+                    );
+                    *count = count.saturating_add(1);
+                    Ok(())
+                })?;
+
                 *maybe_price = Some(price);
                 Self::deposit_event(Event::CurrencyAdded { asset_id: currency });
                 Ok(())
@@ -295,12 +554,115 @@ pub mod pallet {
                 }
 
                 *x = None;
+                AcceptedCurrencyCount::<T>::mutate(|count| *count = count.saturating_sub(1));
 
                 Self::deposit_event(Event::CurrencyRemoved { asset_id: currency });
 
                 Ok(())
             })
         }
+
+        /// Set or remove sponsorship rule of the caller.
+        ///
+        /// Transaction fees of calls matching the rule are paid by the caller in its fee currency instead of the
+        /// signer, as long as the budget of the current period allows. If the caller cannot pay a fee, the signer
+        /// pays it. Transactions with a tip are not sponsored.
+        ///
+        /// The rule must contain at least one call and its calls must not be sponsored by other sponsors.
+        ///
+        /// `SponsorshipDeposit` of native currency for each call of the rule is reserved when a rule is set and
+        /// unreserved when it is removed.
+        ///
+        /// Emits `SponsorshipSet` or `SponsorshipRemoved` event when successful.
+        #[pallet::call_index(3)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_sponsorship(T::MaxSponsoredCalls::get()))]
+        pub fn set_sponsorship(origin: OriginFor<T>, rule: Option<SponsorshipRuleOf<T>>) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+
+            let native_asset = T::NativeAssetId::get();
+
+            match rule {
+                Some(rule) => {
+                    ensure!(!rule.calls.is_empty(), Error::<T>::NoSponsoredCalls);
+                    ensure!(
+                        rule.calls
+                            .iter()
+                            .all(|call| Self::call_sponsor(call).map_or(true, |call_sponsor| call_sponsor == sponsor)),
+                        Error::<T>::CallAlreadySponsored
+                    );
+
+                    let deposit = T::SponsorshipDeposit::get().saturating_mul((rule.calls.len() as u32).into());
+                    let reserved = Self::sponsorship_deposit(&sponsor);
+                    if deposit > reserved {
+                        T::Currencies::reserve(native_asset, &sponsor, deposit.saturating_sub(reserved))?;
+                    } else {
+                        T::Currencies::unreserve(native_asset, &sponsor, reserved.saturating_sub(deposit));
+                    }
+                    SponsorshipDeposits::<T>::insert(&sponsor, deposit);
+
+                    match SponsorshipRules::<T>::get(&sponsor) {
+                        Some(previous) => {
+                            for call in previous.calls.iter() {
+                                CallSponsors::<T>::remove(call);
+                            }
+                        }
+                        None => SponsorCount::<T>::mutate(|count| *count = count.saturating_add(1)),
+                    }
+                    for call in rule.calls.iter() {
+                        CallSponsors::<T>::insert(call, &sponsor);
+                    }
+
+                    SponsorshipRules::<T>::insert(&sponsor, rule.clone());
+
+                    Self::deposit_event(Event::SponsorshipSet { sponsor, rule });
+                }
+                None => {
+                    let rule = SponsorshipRules::<T>::take(&sponsor).ok_or(Error::<T>::SponsorshipNotFound)?;
+                    for call in rule.calls.iter() {
+                        CallSponsors::<T>::remove(call);
+                    }
+                    SponsorCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+                    SponsorshipSpending::<T>::remove(&sponsor);
+
+                    let deposit = SponsorshipDeposits::<T>::take(&sponsor);
+                    T::Currencies::unreserve(native_asset, &sponsor, deposit);
+
+                    Self::deposit_event(Event::SponsorshipRemoved { sponsor });
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Set the order of currencies used to pay transaction fees if the requested currency cannot be used, e.g.
+        /// because of insufficient balance. The native currency is tried first, then `currencies` in given order.
+        ///
+        /// Fallback is disabled if `currencies` is `None`.
+        ///
+        /// Only accepted currencies can be used as fallback.
+        ///
+        /// Emits `FallbackCurrenciesSet` event when successful.
+        #[pallet::call_index(4)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_fallback_currencies())]
+        pub fn set_fallback_currencies(origin: OriginFor<T>, currencies: Option<Vec<AssetIdOf<T>>>) -> DispatchResult {
+            T::AcceptedCurrencyOrigin::ensure_origin(origin)?;
+
+            let currencies = currencies
+                .map(|currencies| {
+                    ensure!(
+                        currencies.iter().all(|c| AcceptedCurrencies::<T>::contains_key(c)),
+                        Error::<T>::UnsupportedCurrency
+                    );
+                    BoundedVec::try_from(currencies).map_err(|_| Error::<T>::TooManyFallbackCurrencies)
+                })
+                .transpose()?;
+
+            FallbackCurrencies::<T>::set(currencies.clone());
+
+            Self::deposit_event(Event::FallbackCurrenciesSet { currencies });
+
+            Ok(())
+        }
     }
 }
 
@@ -311,19 +673,273 @@ where
     fn account_currency(who: &T::AccountId) -> AssetIdOf<T> {
         Pallet::<T>::get_currency(who).unwrap_or_else(T::NativeAssetId::get)
     }
+}
 
+impl<T: Config> Pallet<T> {
+    /// Price of `currency` denominated in native currency. Returns `None` if the currency is not accepted.
+    ///
+    /// The price of an accepted currency is cached for the rest of the block when first requested. The weight of
+    /// fetching, caching and clearing the price is registered then.
     fn get_currency_price(currency: AssetIdOf<T>) -> Option<Price> {
-        if let Some(price) = Self::price(currency) {
-            Some(price)
+        if currency == T::NativeAssetId::get() {
+            return Some(Price::one());
+        }
+
+        if let Some(price) = Self::currency_price(currency) {
+            return Some(price);
+        }
+
+        let fallback_price = Self::currencies(currency)?;
+        let price = Self::fetch_price(currency).unwrap_or(fallback_price);
+        AcceptedCurrencyPrice::<T>::insert(currency, price);
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            Self::price_cache_weight(),
+            DispatchClass::Mandatory,
+        );
+
+        Some(price)
+    }
+
+    /// Price of `currency` denominated in native currency.
+    ///
+    /// Takes the oracle price if the oracle is old enough and not stale, otherwise the spot price if
+    /// `SpotPriceFallback` is enabled.
+    fn fetch_price(currency: AssetIdOf<T>) -> Option<Price> {
+        let native_asset = T::NativeAssetId::get();
+
+        let oracle_price = FreshPriceOracle::<T::Oracle, T::MaxOracleStaleness, T::MinOracleAge>::get_price(
+            currency,
+            native_asset,
+            T::OraclePricePeriod::get(),
+            T::OracleSource::get(),
+        )
+        .ok()
+        .map(|(price, _)| price);
+
+        oracle_price.or_else(|| {
+            if T::SpotPriceFallback::get() {
+                T::SpotPriceProvider::spot_price(currency, native_asset)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Swap buffered fees to native asset as long as `remaining_weight` allows.
+    ///
+    /// Continues after the last entry handled in the previous swap period, so that entries which cannot be swapped
+    /// do not prevent swapping the following ones. At most the balance of the fee receiver is swapped. Entries are
+    /// removed from the buffer if the swap succeeded. Fees which could not be swapped stay buffered and are swapped
+    /// in one of the next swap periods, unless the swap failed `MaxFeeSwapAttempts` times. Returns the consumed
+    /// weight.
+    fn swap_buffered_fees(remaining_weight: Weight) -> Weight {
+        let db_weight = T::DbWeight::get();
+        // reading and writing the cursor
+        let base_weight = db_weight.reads_writes(1, 1);
+        // reading and updating a buffer entry, reading the balance to swap and balances before and after the swap
+        let entry_weight = T::FeeSwap::sell_weight()
+            .saturating_add(T::Oracle::get_price_info_weight())
+            .saturating_add(db_weight.reads_writes(4, 1));
+
+        // Entries are never swapped for free, otherwise the number of swaps would not be bounded by the weight.
+        if entry_weight.ref_time().is_zero() || remaining_weight.any_lt(base_weight.saturating_add(entry_weight)) {
+            return Weight::zero();
+        }
+
+        let max_entries = remaining_weight.saturating_sub(base_weight).ref_time() / entry_weight.ref_time();
+
+        let mut iter = match FeeSwapCursor::<T>::get() {
+            Some(cursor) => BufferedFees::<T>::iter_from(cursor.into_inner()),
+            None => BufferedFees::<T>::iter(),
+        };
+        let entries: Vec<(T::AccountId, AssetIdOf<T>, BufferedFee<BalanceOf<T>>)> =
+            iter.by_ref().take(max_entries as usize).collect();
+
+        if (entries.len() as u64) < max_entries {
+            FeeSwapCursor::<T>::kill();
         } else {
-            // If not loaded in on_init, let's try first the spot price provider again
-            // This is unlikely scenario as the price would be retrieved in on_init for each block
-            if let Some(price) = T::SpotPriceProvider::spot_price(currency, T::NativeAssetId::get()) {
-                Some(price)
+            match BoundedVec::try_from(iter.last_raw_key().to_vec()) {
+                Ok(cursor) => FeeSwapCursor::<T>::put(cursor),
+                Err(_) => {
+                    frame_support::log::warn!(
+                        target: "runtime::transaction-multi-payment",
+                        "Buffered fee storage key is too long to continue swapping fees after it."
+                    );
+                    FeeSwapCursor::<T>::kill();
+                }
+            }
+        }
+
+        let native_asset = T::NativeAssetId::get();
+        for (who, currency, buffered) in entries.iter() {
+            // the buffered fees might have been spent by the fee receiver in the meantime
+            let amount = buffered.amount.min(T::Currencies::free_balance(*currency, who));
+            if amount.is_zero() {
+                BufferedFees::<T>::remove(who, currency);
+                continue;
+            }
+
+            let native_before = T::Currencies::free_balance(native_asset, who);
+            let swapped = Self::min_swap_amount_out(*currency, amount).map_or(false, |min_amount_out| {
+                T::FeeSwap::sell(who, *currency, native_asset, amount, min_amount_out).is_ok()
+            });
+
+            if swapped {
+                BufferedFees::<T>::remove(who, currency);
+                Self::deposit_event(Event::FeesSwapped {
+                    account_id: who.clone(),
+                    asset_id: *currency,
+                    amount_in: amount,
+                    amount_out: T::Currencies::free_balance(native_asset, who).saturating_sub(native_before),
+                });
+            } else if buffered.failed_swaps.saturating_add(1) >= T::MaxFeeSwapAttempts::get() {
+                BufferedFees::<T>::remove(who, currency);
+                Self::deposit_event(Event::FeeSwapAbandoned {
+                    account_id: who.clone(),
+                    asset_id: *currency,
+                    amount,
+                });
             } else {
-                Self::currencies(currency)
+                BufferedFees::<T>::insert(
+                    who,
+                    currency,
+                    BufferedFee {
+                        amount,
+                        failed_swaps: buffered.failed_swaps.saturating_add(1),
+                    },
+                );
+                Self::deposit_event(Event::FeeSwapFailed {
+                    account_id: who.clone(),
+                    asset_id: *currency,
+                    amount,
+                });
+            }
+        }
+
+        base_weight.saturating_add(entry_weight.saturating_mul(entries.len() as u64))
+    }
+
+    /// Minimum amount of native asset to receive for `amount` of `currency` according to the oracle price and
+    /// `MaxFeeSwapSlippage`. Returns `None` if the oracle price is not available.
+    fn min_swap_amount_out(currency: AssetIdOf<T>, amount: BalanceOf<T>) -> Option<BalanceOf<T>> {
+        let (price, _) = FreshPriceOracle::<T::Oracle, T::MaxOracleStaleness, T::MinOracleAge>::get_price(
+            currency,
+            T::NativeAssetId::get(),
+            T::OraclePricePeriod::get(),
+            T::OracleSource::get(),
+        )
+        .ok()?;
+
+        let expected = price.reciprocal()?.saturating_mul_int(amount.saturated_into::<u128>());
+        let min_amount_out = Permill::one()
+            .saturating_sub(T::MaxFeeSwapSlippage::get())
+            .mul_floor(expected);
+
+        Some(min_amount_out.saturated_into())
+    }
+
+    /// Currencies to try paying a fee in, starting with the requested `currency`, followed by the native currency and
+    /// the fallback currencies if fallback is enabled.
+    fn fee_currency_candidates(currency: AssetIdOf<T>) -> Vec<AssetIdOf<T>> {
+        let mut candidates = sp_std::vec![currency];
+
+        if let Some(fallback) = Self::fallback_currencies() {
+            for candidate in sp_std::iter::once(T::NativeAssetId::get()).chain(fallback) {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
             }
         }
+
+        candidates
+    }
+
+    /// Find the sponsor of the call of `who` with a rule allowing `who` and enough budget left to pay `fee`.
+    ///
+    /// The sponsor of the call is checked before the sponsor of all calls of its pallet. The weight of the lookup,
+    /// `WeightInfo::find_sponsor`, is not part of the weight of the call and has to be included in the base weight of
+    /// extrinsics of the runtime, so that it is paid by the signer.
+    fn find_sponsor(who: &T::AccountId, call: &T::RuntimeCall, fee: BalanceOf<T>) -> Option<T::AccountId> {
+        if SponsorCount::<T>::get().is_zero() {
+            return None;
+        }
+
+        // the encoding cost grows with the length of the call, which is paid by the length fee
+        let encoded_call = call.encode();
+        let pallet_index = *encoded_call.first()?;
+        let call_index = *encoded_call.get(1)?;
+
+        [Some(call_index), None].into_iter().find_map(|call_index| {
+            let sponsor = Self::call_sponsor(SponsoredCall {
+                pallet_index,
+                call_index,
+            })?;
+            let rule = Self::sponsorship(&sponsor)?;
+
+            let allowed = sponsor != *who
+                && fee <= rule.max_fee
+                && rule.callers.as_ref().map_or(true, |callers| callers.contains(who))
+                && Self::spent_in_period(&sponsor, rule.period).saturating_add(fee) <= rule.budget;
+
+            if allowed {
+                Some(sponsor)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Start of the budget period which contains the current block.
+    fn period_start(period: T::BlockNumber) -> T::BlockNumber {
+        if period.is_zero() {
+            return Zero::zero();
+        }
+        let now = frame_system::Pallet::<T>::block_number();
+        now.saturating_sub(now % period)
+    }
+
+    /// Fees paid by `sponsor` in the current budget period.
+    fn spent_in_period(sponsor: &T::AccountId, period: T::BlockNumber) -> BalanceOf<T> {
+        let (start, spent) = Self::sponsorship_spending(sponsor);
+        if start == Self::period_start(period) {
+            spent
+        } else {
+            Zero::zero()
+        }
+    }
+
+    /// Add `fee` to the fees paid by `sponsor` in the current budget period.
+    fn record_sponsored_fee(sponsor: &T::AccountId, fee: BalanceOf<T>) {
+        let period = Self::sponsorship(sponsor).map_or_else(Zero::zero, |rule| rule.period);
+        let spent = Self::spent_in_period(sponsor, period);
+        SponsorshipSpending::<T>::insert(sponsor, (Self::period_start(period), spent.saturating_add(fee)));
+    }
+
+    /// Subtract refunded `amount` from the fees paid by `sponsor` in the current budget period.
+    fn refund_sponsored_fee(sponsor: &T::AccountId, amount: BalanceOf<T>) {
+        SponsorshipSpending::<T>::mutate(sponsor, |(_, spent)| *spent = spent.saturating_sub(amount));
+    }
+
+    /// Maximum number of prices cached in a block.
+    ///
+    /// Accepted currencies added before `MaxAcceptedCurrencies` was enforced may exceed it.
+    fn price_cache_limit() -> u32 {
+        AcceptedCurrencyCount::<T>::get().max(T::MaxAcceptedCurrencies::get())
+    }
+
+    /// Weight of caching the price of one currency in `get_currency_price` and clearing it in `on_finalize`.
+    fn price_cache_weight() -> Weight {
+        Self::fetch_price_weight().saturating_add(T::DbWeight::get().reads_writes(2, 2))
+    }
+
+    /// Weight of `fetch_price` when falling back to the spot price.
+    fn fetch_price_weight() -> Weight {
+        let weight = T::Oracle::get_price_info_weight();
+        if T::SpotPriceFallback::get() {
+            weight.saturating_add(T::WeightInfo::get_spot_price())
+        } else {
+            weight
+        }
     }
 }
 
@@ -335,6 +951,34 @@ where
     price.checked_mul_int(fee).map(|f| f.max(One::one()))
 }
 
+/// Split the paid native fee into `(refund, fee, tip)` according to the corrected fee.
+fn split_native_fee<B>(paid_fee: B, corrected_fee: B, tip: B) -> (B, B, B)
+where
+    B: Saturating + Copy,
+{
+    (
+        paid_fee.saturating_sub(corrected_fee),
+        corrected_fee.saturating_sub(tip),
+        tip,
+    )
+}
+
+/// Split the fee paid in non-native currency with `price` into `(refund, fee, tip)` according to the corrected fee.
+fn split_non_native_fee<B>(paid_fee: B, price: FixedU128, corrected_fee: B, tip: B) -> Option<(B, B, B)>
+where
+    B: FixedPointOperand + Ord + One,
+{
+    // calculate corrected_fee in the non-native currency
+    let converted_corrected_fee = convert_fee_with_price(corrected_fee, price)?;
+    let refund = paid_fee.saturating_sub(converted_corrected_fee);
+    let converted_tip = price.checked_mul_int(tip)?;
+    Some((
+        refund,
+        converted_corrected_fee.saturating_sub(converted_tip),
+        converted_tip,
+    ))
+}
+
 /// Deposits all fees to some account
 pub struct DepositAll<T>(PhantomData<T>);
 
@@ -345,13 +989,110 @@ impl<T: Config> DepositFee<T::AccountId, AssetIdOf<T>, BalanceOf<T>> for Deposit
     }
 }
 
+/// Deposits all fees to some account and buffers fees paid in non-native currencies to be swapped to native asset.
+///
+/// Buffered fees are swapped every `FeeSwapPeriod` blocks in `on_idle`.
+pub struct DepositAndSwapFees<T>(PhantomData<T>);
+
+impl<T: Config> DepositFee<T::AccountId, AssetIdOf<T>, BalanceOf<T>> for DepositAndSwapFees<T> {
+    fn deposit_fee(who: &T::AccountId, currency: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+        <T as Config>::Currencies::deposit(currency, who, amount)?;
+
+        if currency != T::NativeAssetId::get() && !amount.is_zero() {
+            BufferedFees::<T>::mutate(who, currency, |buffered| {
+                buffered.amount = buffered.amount.saturating_add(amount)
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Withdraw the predicted `fee` from the sponsor of the transaction of `who` in the currency of the sponsor.
+///
+/// Transactions with a non-zero `tip` are not sponsored, the tip is always paid by `who`. If there is no sponsor or
+/// the sponsor cannot pay, the fee is withdrawn from `who` in the `requested` currency, the
+/// currency set by a `set_currency` call or the account currency, in this order. Falls back to other fee currencies
+/// if the fee cannot be paid in the chosen currency.
+fn withdraw_fee_in<T, MC>(
+    who: &T::AccountId,
+    call: &<T as frame_system::Config>::RuntimeCall,
+    fee: BalanceOf<T>,
+    tip: BalanceOf<T>,
+    requested: Option<AssetIdOf<T>>,
+) -> Result<Option<PaymentInfo<BalanceOf<T>, AssetIdOf<T>, Price, T::AccountId>>, TransactionValidityError>
+where
+    T: Config,
+    MC: MultiCurrency<<T as frame_system::Config>::AccountId, Balance = BalanceOf<T>>,
+    AssetIdOf<T>: Into<MC::CurrencyId>,
+    <T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+    BalanceOf<T>: FixedPointOperand,
+{
+    if fee.is_zero() {
+        return Ok(None);
+    }
+
+    let withdraw = |payer: &T::AccountId, currency: AssetIdOf<T>| -> Option<(AssetIdOf<T>, BalanceOf<T>, Price)> {
+        let price = Pallet::<T>::get_currency_price(currency)?;
+        let converted_fee = convert_fee_with_price(fee, price)?;
+        MC::withdraw(currency.into(), payer, converted_fee).ok()?;
+        Some((currency, converted_fee, price))
+    };
+
+    // sponsors pay in their currency without fallback
+    let sponsored = if tip.is_zero() {
+        Pallet::<T>::find_sponsor(who, call, fee).and_then(|sponsor| {
+            let withdrawn = withdraw(&sponsor, Pallet::<T>::account_currency(&sponsor))?;
+            Some((sponsor, withdrawn))
+        })
+    } else {
+        None
+    };
+
+    if let Some((sponsor, (currency, converted_fee, price))) = sponsored {
+        Pallet::<T>::record_sponsored_fee(&sponsor, fee);
+        return Ok(Some(PaymentInfo::Sponsored {
+            sponsor,
+            native_fee: fee,
+            fee: converted_fee,
+            currency,
+            price,
+        }));
+    }
+
+    let currency = match (requested, call.is_sub_type()) {
+        (Some(currency), _) => currency,
+        (None, Some(Call::set_currency { currency })) => *currency,
+        _ => Pallet::<T>::account_currency(who),
+    };
+
+    let (used_currency, converted_fee, price) = Pallet::<T>::fee_currency_candidates(currency)
+        .into_iter()
+        .find_map(|currency| withdraw(who, currency))
+        .ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
+
+    if used_currency != currency {
+        Pallet::<T>::deposit_event(Event::FeeCurrencyFallback {
+            account_id: who.clone(),
+            requested_asset_id: currency,
+            used_asset_id: used_currency,
+        });
+    }
+
+    if used_currency == T::NativeAssetId::get() {
+        Ok(Some(PaymentInfo::Native(fee)))
+    } else {
+        Ok(Some(PaymentInfo::NonNative(converted_fee, used_currency, price)))
+    }
+}
+
 /// Implements the transaction payment for native as well as non-native currencies
 pub struct TransferFees<MC, DF, FR>(PhantomData<(MC, DF, FR)>);
 
 impl<T, MC, DF, FR> OnChargeTransaction<T> for TransferFees<MC, DF, FR>
 where
     T: Config,
-    MC: MultiCurrency<<T as frame_system::Config>::AccountId>,
+    MC: MultiCurrency<<T as frame_system::Config>::AccountId, Balance = BalanceOf<T>>,
     AssetIdOf<T>: Into<MC::CurrencyId>,
     MC::Balance: FixedPointOperand,
     FR: Get<T::AccountId>,
@@ -359,7 +1100,7 @@ where
     <T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
     BalanceOf<T>: FixedPointOperand,
 {
-    type LiquidityInfo = Option<PaymentInfo<Self::Balance, AssetIdOf<T>, Price>>;
+    type LiquidityInfo = Option<PaymentInfo<Self::Balance, AssetIdOf<T>, Price, T::AccountId>>;
     type Balance = <MC as MultiCurrency<<T as frame_system::Config>::AccountId>>::Balance;
 
     /// Withdraw the predicted fee from the transaction origin.
@@ -370,33 +1111,9 @@ where
         call: &T::RuntimeCall,
         _info: &DispatchInfoOf<T::RuntimeCall>,
         fee: Self::Balance,
-        _tip: Self::Balance,
+        tip: Self::Balance,
     ) -> Result<Self::LiquidityInfo, TransactionValidityError> {
-        if fee.is_zero() {
-            return Ok(None);
-        }
-
-        let currency = match call.is_sub_type() {
-            Some(Call::set_currency { currency }) => *currency,
-            _ => Pallet::<T>::account_currency(who),
-        };
-
-        let price = Pallet::<T>::get_currency_price(currency)
-            .ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
-
-        let converted_fee =
-            convert_fee_with_price(fee, price).ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
-
-        match MC::withdraw(currency.into(), who, converted_fee) {
-            Ok(()) => {
-                if currency == T::NativeAssetId::get() {
-                    Ok(Some(PaymentInfo::Native(fee)))
-                } else {
-                    Ok(Some(PaymentInfo::NonNative(converted_fee, currency, price)))
-                }
-            }
-            Err(_) => Err(InvalidTransaction::Payment.into()),
-        }
+        withdraw_fee_in::<T, MC>(who, call, fee, tip, None)
     }
 
     /// Since the predicted fee might have been too high, parts of the fee may
@@ -415,51 +1132,221 @@ where
 
         if let Some(paid) = already_withdrawn {
             // Calculate how much refund we should return
-            let (currency, refund, fee, tip) = match paid {
-                PaymentInfo::Native(paid_fee) => (
-                    T::NativeAssetId::get().into(),
-                    paid_fee.saturating_sub(corrected_fee),
-                    corrected_fee.saturating_sub(tip),
-                    tip,
-                ),
+            let (payer, currency, refund, fee, tip) = match &paid {
+                PaymentInfo::Native(paid_fee) => {
+                    let (refund, fee, tip) = split_native_fee(*paid_fee, corrected_fee, tip);
+                    (who.clone(), T::NativeAssetId::get().into(), refund, fee, tip)
+                }
                 PaymentInfo::NonNative(paid_fee, currency, price) => {
-                    // calculate corrected_fee in the non-native currency
-                    let converted_corrected_fee = convert_fee_with_price(corrected_fee, price)
-                        .ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
-                    let refund = paid_fee.saturating_sub(converted_corrected_fee);
-                    let converted_tip = price
-                        .checked_mul_int(tip)
+                    let (refund, fee, tip) = split_non_native_fee(*paid_fee, *price, corrected_fee, tip)
                         .ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
-                    (
-                        currency.into(),
-                        refund,
-                        converted_corrected_fee.saturating_sub(converted_tip),
-                        converted_tip,
-                    )
+                    (who.clone(), (*currency).into(), refund, fee, tip)
+                }
+                PaymentInfo::Sponsored {
+                    sponsor,
+                    native_fee,
+                    fee: paid_fee,
+                    currency,
+                    price,
+                } => {
+                    let (refund, fee, tip) = if *currency == T::NativeAssetId::get() {
+                        split_native_fee(*paid_fee, corrected_fee, tip)
+                    } else {
+                        split_non_native_fee(*paid_fee, *price, corrected_fee, tip)
+                            .ok_or(TransactionValidityError::Invalid(InvalidTransaction::Payment))?
+                    };
+
+                    Pallet::<T>::refund_sponsored_fee(sponsor, native_fee.saturating_sub(corrected_fee));
+
+                    (sponsor.clone(), (*currency).into(), refund, fee, tip)
                 }
             };
 
             // refund to the account that paid the fees
-            MC::deposit(currency, who, refund)
+            MC::deposit(currency, &payer, refund)
                 .map_err(|_| TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
 
             // deposit the fee
             DF::deposit_fee(&fee_receiver, currency, fee + tip)
                 .map_err(|_| TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
+
+            if let PaymentInfo::Sponsored {
+                sponsor, native_fee, ..
+            } = paid
+            {
+                Pallet::<T>::deposit_event(Event::FeeSponsored {
+                    sponsor,
+                    account_id: who.clone(),
+                    native_fee_amount: corrected_fee.min(native_fee),
+                });
+            }
         }
 
         Ok(())
     }
 }
 
+impl<T, MC, DF, FR> WithdrawFeeInCurrency<T, AssetIdOf<T>> for TransferFees<MC, DF, FR>
+where
+    T: Config,
+    MC: MultiCurrency<<T as frame_system::Config>::AccountId, Balance = BalanceOf<T>>,
+    AssetIdOf<T>: Into<MC::CurrencyId>,
+    MC::Balance: FixedPointOperand,
+    FR: Get<T::AccountId>,
+    DF: DepositFee<T::AccountId, MC::CurrencyId, MC::Balance>,
+    <T as frame_system::Config>::RuntimeCall: IsSubType<Call<T>>,
+    BalanceOf<T>: FixedPointOperand,
+{
+    fn withdraw_fee_in_currency(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        _info: &DispatchInfoOf<T::RuntimeCall>,
+        fee: Self::Balance,
+        tip: Self::Balance,
+        currency: AssetIdOf<T>,
+    ) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+        withdraw_fee_in::<T, MC>(who, call, fee, tip, Some(currency))
+    }
+}
+
+type PaymentBalanceOf<T> =
+    <<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+
+/// Signed extension to pay the fee of a transaction in the given currency instead of the account currency.
+///
+/// Wraps `ChargeTransactionPayment` and replaces it in the signed extensions of the runtime. The currency must be
+/// native or accepted. The account currency stays unchanged. Without a currency, it behaves like
+/// `ChargeTransactionPayment`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct TransactionFeeCurrency<T: Config> {
+    #[codec(compact)]
+    tip: PaymentBalanceOf<T>,
+    currency: Option<AssetIdOf<T>>,
+}
+
+impl<T: Config> TransactionFeeCurrency<T>
+where
+    T::OnChargeTransaction: WithdrawFeeInCurrency<T, AssetIdOf<T>>,
+    PaymentBalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+    <T as frame_system::Config>::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+    pub fn new(tip: PaymentBalanceOf<T>, currency: Option<AssetIdOf<T>>) -> Self {
+        Self { tip, currency }
+    }
+
+    fn payment(&self) -> ChargeTransactionPayment<T> {
+        ChargeTransactionPayment::<T>::from(self.tip)
+    }
+
+    /// Withdraw the fee in `currency`. Returns the fee in native currency and the withdrawn liquidity.
+    fn withdraw_fee(
+        &self,
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::RuntimeCall,
+        info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+        len: usize,
+        currency: AssetIdOf<T>,
+    ) -> Result<
+        (
+            PaymentBalanceOf<T>,
+            <T::OnChargeTransaction as OnChargeTransaction<T>>::LiquidityInfo,
+        ),
+        TransactionValidityError,
+    > {
+        if currency != T::NativeAssetId::get() && !AcceptedCurrencies::<T>::contains_key(currency) {
+            return Err(InvalidTransaction::Payment.into());
+        }
+
+        let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, self.tip);
+        let liquidity = T::OnChargeTransaction::withdraw_fee_in_currency(who, call, info, fee, self.tip, currency)?;
+
+        Ok((fee, liquidity))
+    }
+}
+
+impl<T: Config> sp_std::fmt::Debug for TransactionFeeCurrency<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(f, "TransactionFeeCurrency<{:?}, {:?}>", self.tip, self.currency)
+    }
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config + Send + Sync> SignedExtension for TransactionFeeCurrency<T>
+where
+    T::OnChargeTransaction: WithdrawFeeInCurrency<T, AssetIdOf<T>>,
+    PaymentBalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+    <T as frame_system::Config>::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+    const IDENTIFIER: &'static str = "TransactionFeeCurrency";
+    type AccountId = T::AccountId;
+    type Call = <T as frame_system::Config>::RuntimeCall;
+    type AdditionalSigned = ();
+    type Pre = <ChargeTransactionPayment<T> as SignedExtension>::Pre;
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match self.currency {
+            Some(currency) => {
+                let (fee, _) = self.withdraw_fee(who, call, info, len, currency)?;
+                Ok(ValidTransaction {
+                    priority: ChargeTransactionPayment::<T>::get_priority(info, len, self.tip, fee),
+                    ..Default::default()
+                })
+            }
+            None => self.payment().validate(who, call, info, len),
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        match self.currency {
+            Some(currency) => {
+                let (_, liquidity) = self.withdraw_fee(who, call, info, len, currency)?;
+                Ok((self.tip, who.clone(), liquidity))
+            }
+            None => self.payment().pre_dispatch(who, call, info, len),
+        }
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result)
+    }
+}
+
 /// We provide an oracle for the price of all currencies accepted as fee payment.
+///
+/// Note: `price` writes to storage. The price of an accepted currency is cached in `AcceptedCurrencyPrice` for the rest
+/// of the block when first requested, so that fees and XCM weight are paid at the same price within a block. The
+/// weight of caching is registered as block weight when the price is fetched and the cache is cleared in
+/// `on_finalize`. Returns `None` for currencies which are not accepted.
 impl<T: Config> NativePriceOracle<AssetIdOf<T>, Price> for Pallet<T> {
     fn price(currency: AssetIdOf<T>) -> Option<Price> {
-        if currency == T::NativeAssetId::get() {
-            Some(Price::one())
-        } else {
-            Pallet::<T>::currency_price(currency)
-        }
+        Pallet::<T>::get_currency_price(currency)
     }
 }
 
@@ -0,0 +1,51 @@
+// This file is part of Basilisk-node.
+
+// Copyright (C) 2020-2022  Intergalactic, Limited (GIB).
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Config;
+use frame_support::weights::Weight;
+
+pub mod v1 {
+    use super::*;
+    use frame_support::log;
+    use frame_support::traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion};
+
+    /// Initialize `AcceptedCurrencyCount` from the existing accepted currencies.
+    ///
+    /// The count may exceed `MaxAcceptedCurrencies`. Then no currency can be added until enough currencies are
+    /// removed, and cached prices of all accepted currencies are still cleared at the end of a block.
+    pub fn migrate<T: Config, P: GetStorageVersion + PalletInfoAccess>() -> Weight {
+        if <P as GetStorageVersion>::on_chain_storage_version() != 0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let count = crate::AcceptedCurrencies::<T>::iter_keys().count() as u64;
+        crate::AcceptedCurrencyCount::<T>::put(count as u32);
+
+        if count > T::MaxAcceptedCurrencies::get() as u64 {
+            log::warn!(
+                target: "runtime::transaction-multi-payment",
+                "Number of accepted currencies {} exceeds MaxAcceptedCurrencies {}",
+                count,
+                T::MaxAcceptedCurrencies::get()
+            );
+        }
+
+        StorageVersion::new(1).put::<P>();
+
+        T::DbWeight::get().reads_writes(count.saturating_add(1), 2)
+    }
+}
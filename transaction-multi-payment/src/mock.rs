@@ -26,7 +26,10 @@ use frame_support::{
     weights::{IdentityFee, Weight},
 };
 use frame_system as system;
-use hydradx_traits::{pools::SpotPriceProvider, AssetPairAccountIdFor};
+use hydradx_traits::{
+    pools::SpotPriceProvider, router::RouterSell, AggregatedPriceInfoOracle, AssetPairAccountIdFor, OraclePeriod,
+    OraclePriceInfo, PriceInfoOracleAdapter, Source,
+};
 use orml_traits::currency::MutationHooks;
 use orml_traits::parameter_type_with_key;
 use pallet_currencies::BasicCurrencyAdapter;
@@ -34,14 +37,18 @@ use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
+    DispatchError, FixedPointNumber, Perbill, Permill,
 };
 use sp_std::cell::RefCell;
+use std::collections::HashMap;
 
 pub type AccountId = u64;
 pub type Balance = u128;
 pub type AssetId = u32;
 pub type Amount = i128;
+pub type BlockNumber = u64;
+/// Price as numerator and denominator, like the prices of the EMA oracle.
+pub type RationalPrice = (u128, u128);
 
 pub const INITIAL_BALANCE: Balance = 1_000_000_000_000_000u128;
 
@@ -64,6 +71,9 @@ const MAX_BLOCK_WEIGHT: Weight = Weight::from_ref_time(1024);
 
 thread_local! {
     static EXTRINSIC_BASE_WEIGHT: RefCell<Weight> = RefCell::new(Weight::zero());
+    static ORACLE_PRICES: RefCell<HashMap<AssetId, OraclePriceInfo<RationalPrice, BlockNumber>>> = RefCell::new(HashMap::new());
+    static SPOT_PRICE_FALLBACK: RefCell<bool> = RefCell::new(true);
+    static SWAP_PRICES: RefCell<HashMap<AssetId, Price>> = RefCell::new(HashMap::new());
 }
 
 pub struct ExtrinsicBaseWeight;
@@ -120,6 +130,30 @@ parameter_types! {
         .build_or_panic();
 
     pub ExchangeFeeRate: (u32, u32) = (2, 1_000);
+
+    pub const OracleSource: Source = *b"testsrc_";
+    pub const OraclePricePeriod: OraclePeriod = OraclePeriod::Short;
+    pub const MaxOracleStaleness: BlockNumber = 5;
+    pub const MinOracleAge: BlockNumber = 3;
+
+    pub const FeeSwapPeriod: BlockNumber = 10;
+    pub const MaxFeeSwapSlippage: Permill = Permill::from_percent(1);
+    pub const MaxFeeSwapAttempts: u32 = 3;
+
+    pub const SponsorshipDeposit: Balance = 1_000;
+    pub const MaxSponsoredCallers: u32 = 2;
+    pub const MaxSponsoredCalls: u32 = 2;
+    pub const MaxFallbackCurrencies: u32 = 2;
+    pub const MaxAcceptedCurrencies: u32 = 6;
+}
+
+pub const FEE_SWAP_WEIGHT: Weight = Weight::from_ref_time(100);
+
+pub struct SpotPriceFallback;
+impl Get<bool> for SpotPriceFallback {
+    fn get() -> bool {
+        SPOT_PRICE_FALLBACK.with(|v| *v.borrow())
+    }
 }
 
 impl system::Config for Test {
@@ -154,6 +188,21 @@ impl Config for Test {
     type AcceptedCurrencyOrigin = frame_system::EnsureRoot<u64>;
     type Currencies = Currencies;
     type SpotPriceProvider = SpotPrice;
+    type Oracle = PriceInfoOracleAdapter<RationalOracle, AssetId, RationalPrice>;
+    type OracleSource = OracleSource;
+    type OraclePricePeriod = OraclePricePeriod;
+    type MaxOracleStaleness = MaxOracleStaleness;
+    type MinOracleAge = MinOracleAge;
+    type SpotPriceFallback = SpotPriceFallback;
+    type FeeSwap = FeeSwap;
+    type FeeSwapPeriod = FeeSwapPeriod;
+    type MaxFeeSwapSlippage = MaxFeeSwapSlippage;
+    type MaxFeeSwapAttempts = MaxFeeSwapAttempts;
+    type SponsorshipDeposit = SponsorshipDeposit;
+    type MaxSponsoredCallers = MaxSponsoredCallers;
+    type MaxSponsoredCalls = MaxSponsoredCalls;
+    type MaxFallbackCurrencies = MaxFallbackCurrencies;
+    type MaxAcceptedCurrencies = MaxAcceptedCurrencies;
     type WeightInfo = ();
     type WeightToFee = IdentityFee<Balance>;
     type NativeAssetId = HdxAssetId;
@@ -211,6 +260,56 @@ impl SpotPriceProvider<AssetId> for SpotPrice {
     }
 }
 
+/// Oracle with rational prices, used via `PriceInfoOracleAdapter`.
+pub struct RationalOracle;
+
+impl AggregatedPriceInfoOracle<AssetId, BlockNumber, RationalPrice> for RationalOracle {
+    type Error = ();
+
+    fn get_price_info(
+        asset_a: AssetId,
+        asset_b: AssetId,
+        period: OraclePeriod,
+        source: Source,
+    ) -> Result<OraclePriceInfo<RationalPrice, BlockNumber>, Self::Error> {
+        assert_eq!(asset_b, HDX);
+        assert_eq!(period, OraclePricePeriod::get());
+        assert_eq!(source, OracleSource::get());
+        ORACLE_PRICES.with(|v| v.borrow().get(&asset_a).copied()).ok_or(())
+    }
+
+    fn get_price_info_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Swaps assets to native asset with the price set by `ExtBuilder::with_swap_price`.
+pub struct FeeSwap;
+
+impl RouterSell<AccountId, AssetId, Balance> for FeeSwap {
+    fn sell(
+        who: &AccountId,
+        asset_in: AssetId,
+        asset_out: AssetId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> DispatchResult {
+        assert_eq!(asset_out, HDX);
+        let price = SWAP_PRICES
+            .with(|v| v.borrow().get(&asset_in).copied())
+            .ok_or(DispatchError::Other("no pool"))?;
+        let amount_out = price.reciprocal().unwrap().saturating_mul_int(amount_in);
+        ensure!(amount_out >= min_amount_out, DispatchError::Other("limit reached"));
+
+        Currencies::withdraw(asset_in, who, amount_in)?;
+        Currencies::deposit(asset_out, who, amount_out)
+    }
+
+    fn sell_weight() -> Weight {
+        FEE_SWAP_WEIGHT
+    }
+}
+
 parameter_type_with_key! {
     pub ExistentialDeposits: |currency_id: AssetId| -> Balance {
         match *currency_id {
@@ -264,6 +363,10 @@ pub struct ExtBuilder {
     native_balances: Vec<(AccountId, Balance)>,
     endowed_accounts: Vec<(AccountId, AssetId, Balance)>,
     account_currencies: Vec<(AccountId, AssetId)>,
+    oracle_prices: Vec<(AssetId, Price, BlockNumber)>,
+    oracle_age: BlockNumber,
+    spot_price_fallback: bool,
+    swap_prices: Vec<(AssetId, Price)>,
 }
 
 impl Default for ExtBuilder {
@@ -278,6 +381,10 @@ impl Default for ExtBuilder {
             ],
 
             account_currencies: vec![],
+            oracle_prices: vec![],
+            oracle_age: 10,
+            spot_price_fallback: true,
+            swap_prices: vec![],
         }
     }
 }
@@ -299,8 +406,43 @@ impl ExtBuilder {
         self.account_currencies = account_currencies;
         self
     }
+    /// Set oracle price of `asset` which was last updated `blocks_since_update` blocks ago.
+    pub fn with_oracle_price(mut self, asset: AssetId, price: Price, blocks_since_update: BlockNumber) -> Self {
+        self.oracle_prices.push((asset, price, blocks_since_update));
+        self
+    }
+    pub fn with_oracle_age(mut self, oracle_age: BlockNumber) -> Self {
+        self.oracle_age = oracle_age;
+        self
+    }
+    pub fn with_swap_price(mut self, asset: AssetId, price: Price) -> Self {
+        self.swap_prices.push((asset, price));
+        self
+    }
+    pub fn without_spot_price_fallback(mut self) -> Self {
+        self.spot_price_fallback = false;
+        self
+    }
     fn set_constants(&self) {
         EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow_mut() = self.base_weight);
+        ORACLE_PRICES.with(|v| {
+            *v.borrow_mut() = self
+                .oracle_prices
+                .iter()
+                .map(|(asset, price, blocks_since_update)| {
+                    (
+                        *asset,
+                        OraclePriceInfo {
+                            price: (price.into_inner(), Price::accuracy()),
+                            oracle_age: self.oracle_age,
+                            blocks_since_update: *blocks_since_update,
+                        },
+                    )
+                })
+                .collect()
+        });
+        SPOT_PRICE_FALLBACK.with(|v| *v.borrow_mut() = self.spot_price_fallback);
+        SWAP_PRICES.with(|v| *v.borrow_mut() = self.swap_prices.iter().copied().collect());
     }
     pub fn build(self) -> sp_io::TestExternalities {
         use frame_support::traits::OnInitialize;
@@ -16,7 +16,10 @@
 // limitations under the License.
 
 pub use crate::{mock::*, Config, Error};
-use crate::{AcceptedCurrencies, AcceptedCurrencyPrice, Event, PaymentInfo, Price};
+use crate::{
+    AcceptedCurrencies, AcceptedCurrencyCount, AcceptedCurrencyPrice, BufferedFees, DepositAndSwapFees, DepositFee,
+    Event, PaymentInfo, Price, SponsoredCall, SponsorshipRule, SponsorshipRuleOf, TransactionFeeCurrency,
+};
 
 use frame_support::{
     assert_noop, assert_ok,
@@ -28,48 +31,75 @@ use frame_support::{
 use orml_traits::MultiCurrency;
 use pallet_balances::Call as BalancesCall;
 use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
 
 const CALL: &<Test as frame_system::Config>::RuntimeCall =
     &RuntimeCall::Balances(BalancesCall::transfer { dest: 2, value: 69 });
 
 #[test]
-fn on_initialize_should_fill_storage_with_prices() {
-    // Arrange
+fn currency_price_should_be_cached_when_used() {
+    use hydradx_traits::NativePriceOracle;
+
     ExtBuilder::default().build().execute_with(|| {
+        // prices are not fetched in on_initialize
+        assert_eq!(<AcceptedCurrencyPrice<Test>>::iter_values().next(), None);
+
         // Act
-        let current = System::block_number();
-        PaymentPallet::on_finalize(current);
-        // the block number is not important here and can stay the same
-        PaymentPallet::on_initialize(current);
+        // fallback price
+        assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(1.5)));
+        // price from the spot price provider
+        assert_eq!(
+            PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+            Some(Price::from_float(0.1))
+        );
+        // not supported
+        assert_eq!(PaymentPallet::price(UNSUPPORTED_CURRENCY), None);
 
         // Assert
-        // verify that all accepted currencies have the price set
-        let iter = <AcceptedCurrencies<Test>>::iter();
-        for (asset_id, _) in iter {
-            assert!(<AcceptedCurrencyPrice<Test>>::contains_key(asset_id));
-        }
-
-        // fallback price
         assert_eq!(
             PaymentPallet::currency_price(SUPPORTED_CURRENCY),
             Some(Price::from_float(1.5))
         );
-        // price from the spot price provider
         assert_eq!(
             PaymentPallet::currency_price(SUPPORTED_CURRENCY_WITH_PRICE),
             Some(Price::from_float(0.1))
         );
-        // not supported
+        // only used currencies are cached
+        assert_eq!(PaymentPallet::currency_price(HIGH_ED_CURRENCY), None);
         assert_eq!(PaymentPallet::currency_price(UNSUPPORTED_CURRENCY), None);
     });
 }
 
+#[test]
+fn cached_price_should_be_used_within_block() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+            Some(Price::from_float(0.1))
+        );
+
+        // Act
+        <AcceptedCurrencyPrice<Test>>::insert(SUPPORTED_CURRENCY_WITH_PRICE, Price::from_float(0.2));
+
+        // Assert
+        assert_eq!(
+            PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+            Some(Price::from_float(0.2))
+        );
+    });
+}
+
 #[test]
 fn on_finalize_should_remove_prices_from_storage() {
+    use hydradx_traits::NativePriceOracle;
+
     // Arrange
     ExtBuilder::default().build().execute_with(|| {
         let current = System::block_number();
 
+        assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(1.5)));
         // verify that the storage is not empty
         assert_eq!(
             PaymentPallet::currency_price(SUPPORTED_CURRENCY),
@@ -85,6 +115,47 @@ fn on_finalize_should_remove_prices_from_storage() {
     });
 }
 
+#[test]
+fn on_initialize_should_not_charge_weight_of_price_fetches() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(PaymentPallet::on_initialize(System::block_number()), Weight::zero());
+    });
+}
+
+#[test]
+fn price_fetch_weight_should_be_registered_once_per_currency_and_block() {
+    use crate::weights::WeightInfo;
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(System::block_weight().total(), Weight::zero());
+
+        assert!(PaymentPallet::price(SUPPORTED_CURRENCY).is_some());
+        assert!(PaymentPallet::price(SUPPORTED_CURRENCY).is_some());
+        assert_eq!(System::block_weight().total(), <() as WeightInfo>::get_spot_price());
+
+        assert!(PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE).is_some());
+        assert_eq!(
+            System::block_weight().total(),
+            <() as WeightInfo>::get_spot_price().saturating_mul(2)
+        );
+    });
+}
+
+#[test]
+fn price_should_not_be_fetched_for_currency_which_is_not_accepted() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default()
+        .with_oracle_price(UNSUPPORTED_CURRENCY, Price::from(2), 0)
+        .build()
+        .execute_with(|| {
+            assert_eq!(PaymentPallet::price(UNSUPPORTED_CURRENCY), None);
+            assert_eq!(PaymentPallet::currency_price(UNSUPPORTED_CURRENCY), None);
+            assert_eq!(System::block_weight().total(), Weight::zero());
+        });
+}
+
 #[test]
 fn set_unsupported_currency() {
     ExtBuilder::default().build().execute_with(|| {
@@ -289,6 +360,72 @@ fn add_new_accepted_currency() {
     });
 }
 
+#[test]
+fn add_currency_should_fail_when_max_accepted_currencies_reached() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(PaymentPallet::add_currency(RuntimeOrigin::root(), 100, Price::from(1)));
+        assert_eq!(AcceptedCurrencyCount::<Test>::get(), MaxAcceptedCurrencies::get());
+
+        assert_noop!(
+            PaymentPallet::add_currency(RuntimeOrigin::root(), 101, Price::from(1)),
+            Error::<Test>::TooManyAcceptedCurrencies
+        );
+
+        // removing a currency makes room for another one
+        assert_ok!(PaymentPallet::remove_currency(RuntimeOrigin::root(), 100));
+        assert_ok!(PaymentPallet::add_currency(RuntimeOrigin::root(), 101, Price::from(1)));
+        assert_eq!(AcceptedCurrencyCount::<Test>::get(), MaxAcceptedCurrencies::get());
+    });
+}
+
+#[test]
+fn migration_should_initialize_accepted_currency_count() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    ExtBuilder::default().build().execute_with(|| {
+        AcceptedCurrencyCount::<Test>::kill();
+        StorageVersion::new(0).put::<PaymentPallet>();
+
+        // Act
+        crate::migration::v1::migrate::<Test, PaymentPallet>();
+
+        // Assert
+        assert_eq!(AcceptedCurrencyCount::<Test>::get(), 5);
+        assert_eq!(PaymentPallet::on_chain_storage_version(), 1);
+    });
+}
+
+#[test]
+fn on_finalize_should_remove_all_prices_when_migrated_count_exceeds_max_accepted_currencies() {
+    use frame_support::traits::StorageVersion;
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default().build().execute_with(|| {
+        for currency in 100..103 {
+            AcceptedCurrencies::<Test>::insert(currency, Price::from(1));
+        }
+        AcceptedCurrencyCount::<Test>::kill();
+        StorageVersion::new(0).put::<PaymentPallet>();
+
+        crate::migration::v1::migrate::<Test, PaymentPallet>();
+        assert!(AcceptedCurrencyCount::<Test>::get() > MaxAcceptedCurrencies::get());
+
+        for (currency, _) in AcceptedCurrencies::<Test>::iter() {
+            assert!(PaymentPallet::price(currency).is_some());
+        }
+        assert_eq!(
+            <AcceptedCurrencyPrice<Test>>::iter().count() as u32,
+            AcceptedCurrencyCount::<Test>::get()
+        );
+
+        // Act
+        PaymentPallet::on_finalize(System::block_number());
+
+        // Assert
+        assert_eq!(<AcceptedCurrencyPrice<Test>>::iter_values().next(), None);
+    });
+}
+
 #[test]
 fn removed_accepted_currency() {
     ExtBuilder::default().base_weight(5).build().execute_with(|| {
@@ -1118,3 +1255,1021 @@ fn returns_prices_for_supported_currencies() {
         );
     });
 }
+
+#[test]
+fn price_should_prefer_oracle_price() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from_float(2.5), 0)
+        .with_oracle_price(SUPPORTED_CURRENCY_WITH_PRICE, Price::from_float(0.2), 5)
+        .build()
+        .execute_with(|| {
+            assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(2.5)));
+            assert_eq!(
+                PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+                Some(Price::from_float(0.2))
+            );
+        });
+}
+
+#[test]
+fn stale_oracle_price_should_fall_back_to_spot_price() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from_float(2.5), 6)
+        .with_oracle_price(SUPPORTED_CURRENCY_WITH_PRICE, Price::from_float(0.2), 6)
+        .build()
+        .execute_with(|| {
+            // static fallback price
+            assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(1.5)));
+            // spot price
+            assert_eq!(
+                PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+                Some(Price::from_float(0.1))
+            );
+        });
+}
+
+#[test]
+fn oracle_price_should_not_be_used_when_oracle_is_younger_than_min_age() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from_float(2.5), 0)
+        .with_oracle_price(SUPPORTED_CURRENCY_WITH_PRICE, Price::from_float(0.2), 0)
+        .with_oracle_age(MinOracleAge::get() - 1)
+        .build()
+        .execute_with(|| {
+            // static fallback price
+            assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(1.5)));
+            // spot price
+            assert_eq!(
+                PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+                Some(Price::from_float(0.1))
+            );
+        });
+}
+
+#[test]
+fn spot_price_should_not_be_used_when_fallback_is_disabled() {
+    use hydradx_traits::NativePriceOracle;
+
+    ExtBuilder::default()
+        .without_spot_price_fallback()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from_float(2.5), 0)
+        .build()
+        .execute_with(|| {
+            assert_eq!(PaymentPallet::price(SUPPORTED_CURRENCY), Some(Price::from_float(2.5)));
+            // static fallback price instead of spot price
+            assert_eq!(
+                PaymentPallet::price(SUPPORTED_CURRENCY_WITH_PRICE),
+                Some(Price::from_float(0.5))
+            );
+        });
+}
+
+#[test]
+fn fee_should_be_converted_with_oracle_price() {
+    const CHARLIE: AccountId = 5;
+
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_tokens(CHARLIE, SUPPORTED_CURRENCY_WITH_PRICE, 10_000)
+        .with_currencies(vec![(CHARLIE, SUPPORTED_CURRENCY_WITH_PRICE)])
+        .with_oracle_price(SUPPORTED_CURRENCY_WITH_PRICE, Price::from(2), 0)
+        .build()
+        .execute_with(|| {
+            let len = 1000;
+            let info = info_from_weight(Weight::from_ref_time(5));
+
+            assert!(ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .is_ok());
+
+            // 1010 native converted with oracle price of 2
+            assert_eq!(Tokens::free_balance(SUPPORTED_CURRENCY_WITH_PRICE, &CHARLIE), 7980);
+        });
+}
+
+#[test]
+fn deposit_and_swap_fees_should_buffer_non_native_fees() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(&FEE_RECEIVER, HDX, 1_000));
+        assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+            &FEE_RECEIVER,
+            SUPPORTED_CURRENCY,
+            1_000
+        ));
+        assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+            &FEE_RECEIVER,
+            SUPPORTED_CURRENCY,
+            500
+        ));
+
+        assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 1_000);
+        assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 1_500);
+        assert_eq!(PaymentPallet::buffered_fees(FEE_RECEIVER, HDX).amount, 0);
+        assert_eq!(
+            PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount,
+            1_500
+        );
+    });
+}
+
+#[test]
+fn on_idle_should_swap_buffered_fees_to_native_asset() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from_rational(201, 100))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+
+            // not a swap period block
+            assert_eq!(PaymentPallet::on_idle(9, Weight::MAX), Weight::zero());
+            assert_eq!(
+                PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount,
+                1_000
+            );
+
+            assert_eq!(PaymentPallet::on_idle(10, Weight::MAX), FEE_SWAP_WEIGHT);
+
+            assert_eq!(PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount, 0);
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 0);
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 497);
+            expect_events(vec![Event::FeesSwapped {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount_in: 1_000,
+                amount_out: 497,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn on_idle_should_not_swap_fees_when_slippage_is_exceeded() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from_rational(203, 100))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+
+            PaymentPallet::on_idle(10, Weight::MAX);
+
+            // kept buffered to be retried
+            assert_eq!(
+                PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount,
+                1_000
+            );
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 1_000);
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 0);
+            expect_events(vec![Event::FeeSwapFailed {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount: 1_000,
+            }
+            .into()]);
+
+            // fees collected in the meantime are swapped together with the buffered ones
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                500
+            ));
+            PaymentPallet::on_idle(20, Weight::MAX);
+
+            assert_eq!(
+                PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount,
+                1_500
+            );
+            expect_events(vec![Event::FeeSwapFailed {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount: 1_500,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn on_idle_should_not_swap_fees_without_oracle_price() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 6)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from(2))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+
+            PaymentPallet::on_idle(10, Weight::MAX);
+
+            assert_eq!(
+                PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).amount,
+                1_000
+            );
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 1_000);
+            expect_events(vec![Event::FeeSwapFailed {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount: 1_000,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn on_idle_should_swap_only_as_many_entries_as_weight_allows() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_oracle_price(HIGH_ED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from(2))
+        .with_swap_price(HIGH_ED_CURRENCY, Price::from(2))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                HIGH_ED_CURRENCY,
+                1_000
+            ));
+
+            assert_eq!(
+                PaymentPallet::on_idle(10, FEE_SWAP_WEIGHT.saturating_sub(Weight::from_ref_time(1))),
+                Weight::zero()
+            );
+            assert_eq!(PaymentPallet::on_idle(10, FEE_SWAP_WEIGHT), FEE_SWAP_WEIGHT);
+            assert_eq!(BufferedFees::<Test>::iter().count(), 1);
+
+            assert_eq!(PaymentPallet::on_idle(20, FEE_SWAP_WEIGHT), FEE_SWAP_WEIGHT);
+            assert_eq!(BufferedFees::<Test>::iter().count(), 0);
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 1_000);
+        });
+}
+
+#[test]
+fn on_idle_should_continue_after_entries_handled_in_previous_period() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_oracle_price(HIGH_ED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(HIGH_ED_CURRENCY, Price::from(2))
+        .build()
+        .execute_with(|| {
+            // fees in SUPPORTED_CURRENCY cannot be swapped
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                HIGH_ED_CURRENCY,
+                1_000
+            ));
+
+            // each entry is handled once regardless of the order of the entries
+            assert_eq!(PaymentPallet::on_idle(10, FEE_SWAP_WEIGHT), FEE_SWAP_WEIGHT);
+            assert_eq!(PaymentPallet::on_idle(20, FEE_SWAP_WEIGHT), FEE_SWAP_WEIGHT);
+
+            assert_eq!(PaymentPallet::buffered_fees(FEE_RECEIVER, HIGH_ED_CURRENCY).amount, 0);
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 500);
+            assert_eq!(
+                PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).failed_swaps,
+                1
+            );
+        });
+}
+
+#[test]
+fn on_idle_should_swap_at_most_balance_of_fee_receiver() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from(2))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+            assert_ok!(Currencies::withdraw(SUPPORTED_CURRENCY, &FEE_RECEIVER, 400));
+
+            PaymentPallet::on_idle(10, Weight::MAX);
+
+            assert!(!BufferedFees::<Test>::contains_key(FEE_RECEIVER, SUPPORTED_CURRENCY));
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 0);
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 300);
+            expect_events(vec![Event::FeesSwapped {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount_in: 600,
+                amount_out: 300,
+            }
+            .into()]);
+        });
+}
+
+#[test]
+fn on_idle_should_remove_buffered_fees_when_fee_receiver_spent_them() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .with_swap_price(SUPPORTED_CURRENCY, Price::from(2))
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+            assert_ok!(Currencies::withdraw(SUPPORTED_CURRENCY, &FEE_RECEIVER, 1_000));
+
+            PaymentPallet::on_idle(10, Weight::MAX);
+
+            assert!(!BufferedFees::<Test>::contains_key(FEE_RECEIVER, SUPPORTED_CURRENCY));
+            assert_eq!(Currencies::free_balance(HDX, &FEE_RECEIVER), 0);
+        });
+}
+
+#[test]
+fn on_idle_should_abandon_fees_which_failed_to_swap_max_attempts_times() {
+    ExtBuilder::default()
+        .with_oracle_price(SUPPORTED_CURRENCY, Price::from(2), 0)
+        .build()
+        .execute_with(|| {
+            assert_ok!(DepositAndSwapFees::<Test>::deposit_fee(
+                &FEE_RECEIVER,
+                SUPPORTED_CURRENCY,
+                1_000
+            ));
+
+            for attempt in 1..MaxFeeSwapAttempts::get() {
+                PaymentPallet::on_idle(10 * attempt as u64, Weight::MAX);
+                assert_eq!(
+                    PaymentPallet::buffered_fees(FEE_RECEIVER, SUPPORTED_CURRENCY).failed_swaps,
+                    attempt
+                );
+            }
+
+            PaymentPallet::on_idle(10 * MaxFeeSwapAttempts::get() as u64, Weight::MAX);
+
+            assert!(!BufferedFees::<Test>::contains_key(FEE_RECEIVER, SUPPORTED_CURRENCY));
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 1_000);
+            expect_events(vec![Event::FeeSwapAbandoned {
+                account_id: FEE_RECEIVER,
+                asset_id: SUPPORTED_CURRENCY,
+                amount: 1_000,
+            }
+            .into()]);
+
+            // swapping is not retried
+            assert_eq!(
+                PaymentPallet::on_idle(10 * (MaxFeeSwapAttempts::get() as u64 + 1), Weight::MAX),
+                Weight::zero()
+            );
+        });
+}
+
+#[test]
+fn fee_should_be_paid_in_currency_of_transaction_fee_currency_extension() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .with_currencies(vec![(ALICE, SUPPORTED_CURRENCY_WITH_PRICE)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            let extension = TransactionFeeCurrency::<Test>::new(0, Some(SUPPORTED_CURRENCY));
+
+            let pre = extension.pre_dispatch(&ALICE, CALL, &info, len);
+            assert!(pre.is_ok());
+
+            assert_eq!(
+                Currencies::free_balance(SUPPORTED_CURRENCY, &ALICE),
+                999_999_999_999_970
+            );
+            assert_eq!(
+                Currencies::free_balance(SUPPORTED_CURRENCY_WITH_PRICE, &ALICE),
+                INITIAL_BALANCE
+            );
+
+            assert_ok!(TransactionFeeCurrency::<Test>::post_dispatch(
+                Some(pre.unwrap()),
+                &info,
+                &default_post_info(),
+                len,
+                &Ok(())
+            ));
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 30);
+
+            // account currency is not changed
+            assert_eq!(PaymentPallet::get_currency(ALICE), Some(SUPPORTED_CURRENCY_WITH_PRICE));
+        });
+}
+
+#[test]
+fn fee_should_be_paid_in_account_currency_when_transaction_fee_currency_is_not_set() {
+    ExtBuilder::default().base_weight(5).build().execute_with(|| {
+        let len = 10;
+        let info = info_from_weight(Weight::from_ref_time(5));
+        let extension = TransactionFeeCurrency::<Test>::new(0, None);
+
+        assert_ok!(extension.pre_dispatch(&ALICE, CALL, &info, len));
+
+        assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - 20);
+        assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &ALICE), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn transaction_fee_currency_should_be_native_or_accepted() {
+    ExtBuilder::default().base_weight(5).build().execute_with(|| {
+        let len = 10;
+        let info = info_from_weight(Weight::from_ref_time(5));
+
+        assert_eq!(
+            TransactionFeeCurrency::<Test>::new(0, Some(UNSUPPORTED_CURRENCY)).validate(&ALICE, CALL, &info, len),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Payment))
+        );
+        assert_eq!(
+            TransactionFeeCurrency::<Test>::new(0, Some(UNSUPPORTED_CURRENCY)).pre_dispatch(&ALICE, CALL, &info, len),
+            Err(TransactionValidityError::Invalid(InvalidTransaction::Payment))
+        );
+        assert_ok!(TransactionFeeCurrency::<Test>::new(0, Some(HDX)).validate(&ALICE, CALL, &info, len));
+        assert_ok!(TransactionFeeCurrency::<Test>::new(0, Some(SUPPORTED_CURRENCY)).validate(&ALICE, CALL, &info, len));
+    });
+}
+
+const CHARLIE: AccountId = 5;
+// index of the balances pallet in the mock runtime
+const BALANCES_PALLET_INDEX: u8 = 3;
+
+fn sponsorship_rule(
+    callers: Option<Vec<AccountId>>,
+    calls: Vec<SponsoredCall>,
+    max_fee: Balance,
+    budget: Balance,
+    period: u64,
+) -> SponsorshipRuleOf<Test> {
+    SponsorshipRule {
+        callers: callers.map(|callers| callers.try_into().unwrap()),
+        calls: calls.try_into().unwrap(),
+        max_fee,
+        budget,
+        period,
+    }
+}
+
+fn balances_calls() -> Vec<SponsoredCall> {
+    vec![SponsoredCall {
+        pallet_index: BALANCES_PALLET_INDEX,
+        call_index: None,
+    }]
+}
+
+#[test]
+fn set_sponsorship_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let rule = sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 100, 1_000, 10);
+
+        assert_ok!(PaymentPallet::set_sponsorship(
+            RuntimeOrigin::signed(ALICE),
+            Some(rule.clone())
+        ));
+        assert_eq!(PaymentPallet::sponsorship(ALICE), Some(rule.clone()));
+        assert_eq!(PaymentPallet::sponsorship_deposit(ALICE), SponsorshipDeposit::get());
+        assert_eq!(Balances::reserved_balance(ALICE), SponsorshipDeposit::get());
+        expect_events(vec![Event::SponsorshipSet { sponsor: ALICE, rule }.into()]);
+
+        assert_ok!(PaymentPallet::set_sponsorship(RuntimeOrigin::signed(ALICE), None));
+        assert_eq!(PaymentPallet::sponsorship(ALICE), None);
+        assert_eq!(PaymentPallet::sponsorship_deposit(ALICE), 0);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        expect_events(vec![Event::SponsorshipRemoved { sponsor: ALICE }.into()]);
+
+        assert_noop!(
+            PaymentPallet::set_sponsorship(RuntimeOrigin::signed(ALICE), None),
+            Error::<Test>::SponsorshipNotFound
+        );
+    });
+}
+
+#[test]
+fn set_sponsorship_should_not_reserve_deposit_again_when_rule_is_updated() {
+    ExtBuilder::default().build().execute_with(|| {
+        for budget in [1_000, 2_000] {
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(None, balances_calls(), 100, budget, 10))
+            ));
+        }
+
+        assert_eq!(Balances::reserved_balance(ALICE), SponsorshipDeposit::get());
+    });
+}
+
+#[test]
+fn set_sponsorship_should_reserve_deposit_for_each_call() {
+    ExtBuilder::default().build().execute_with(|| {
+        let calls = vec![
+            SponsoredCall {
+                pallet_index: BALANCES_PALLET_INDEX,
+                call_index: Some(0),
+            },
+            SponsoredCall {
+                pallet_index: BALANCES_PALLET_INDEX,
+                call_index: Some(1),
+            },
+        ];
+
+        assert_ok!(PaymentPallet::set_sponsorship(
+            RuntimeOrigin::signed(ALICE),
+            Some(sponsorship_rule(None, calls, 100, 1_000, 10))
+        ));
+        assert_eq!(PaymentPallet::sponsorship_deposit(ALICE), 2 * SponsorshipDeposit::get());
+        assert_eq!(Balances::reserved_balance(ALICE), 2 * SponsorshipDeposit::get());
+
+        assert_ok!(PaymentPallet::set_sponsorship(
+            RuntimeOrigin::signed(ALICE),
+            Some(sponsorship_rule(None, balances_calls(), 100, 1_000, 10))
+        ));
+        assert_eq!(PaymentPallet::sponsorship_deposit(ALICE), SponsorshipDeposit::get());
+        assert_eq!(Balances::reserved_balance(ALICE), SponsorshipDeposit::get());
+    });
+}
+
+#[test]
+fn set_sponsorship_should_fail_when_deposit_cannot_be_reserved() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(CHARLIE),
+                Some(sponsorship_rule(None, balances_calls(), 100, 1_000, 10))
+            ),
+            pallet_balances::Error::<Test>::InsufficientBalance
+        );
+    });
+}
+
+#[test]
+fn set_sponsorship_should_fail_without_calls() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(None, vec![], 100, 1_000, 10))
+            ),
+            Error::<Test>::NoSponsoredCalls
+        );
+    });
+}
+
+#[test]
+fn set_sponsorship_should_fail_when_call_is_sponsored_by_other_sponsor() {
+    ExtBuilder::default()
+        .account_native_balance(BOB, 10_000)
+        .build()
+        .execute_with(|| {
+            let rule = sponsorship_rule(None, balances_calls(), 100, 1_000, 10);
+
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(rule.clone())
+            ));
+            // updating an existing rule is allowed
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(rule.clone())
+            ));
+            assert_noop!(
+                PaymentPallet::set_sponsorship(RuntimeOrigin::signed(BOB), Some(rule.clone())),
+                Error::<Test>::CallAlreadySponsored
+            );
+            assert_eq!(PaymentPallet::call_sponsor(balances_calls()[0]), Some(ALICE));
+
+            // calls are released when the rule is removed
+            assert_ok!(PaymentPallet::set_sponsorship(RuntimeOrigin::signed(ALICE), None));
+            assert_eq!(PaymentPallet::call_sponsor(balances_calls()[0]), None);
+            assert_ok!(PaymentPallet::set_sponsorship(RuntimeOrigin::signed(BOB), Some(rule)));
+            assert_eq!(PaymentPallet::call_sponsor(balances_calls()[0]), Some(BOB));
+        });
+}
+
+#[test]
+fn sponsor_should_pay_and_get_refund_of_fee_when_rule_matches() {
+    ExtBuilder::default().base_weight(5).build().execute_with(|| {
+        let len = 10;
+        let info = info_from_weight(Weight::from_ref_time(5));
+        assert_ok!(PaymentPallet::set_sponsorship(
+            RuntimeOrigin::signed(ALICE),
+            Some(sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 20, 100, 10))
+        ));
+
+        let pre = ChargeTransactionPayment::<Test>::from(0)
+            .pre_dispatch(&CHARLIE, CALL, &info, len)
+            .unwrap();
+        assert_eq!(
+            pre,
+            (
+                0,
+                CHARLIE,
+                Some(PaymentInfo::Sponsored {
+                    sponsor: ALICE,
+                    native_fee: 20,
+                    fee: 20,
+                    currency: HDX,
+                    price: Price::from(1),
+                })
+            )
+        );
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - SponsorshipDeposit::get() - 20
+        );
+        assert_eq!(PaymentPallet::sponsorship_spending(ALICE), (0, 20));
+
+        assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+            Some(pre),
+            &info,
+            &post_info_from_weight(Weight::from_ref_time(3)),
+            len,
+            &Ok(())
+        ));
+
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - SponsorshipDeposit::get() - 18
+        );
+        assert_eq!(Balances::free_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 18);
+        assert_eq!(PaymentPallet::sponsorship_spending(ALICE), (0, 18));
+        expect_events(vec![Event::FeeSponsored {
+            sponsor: ALICE,
+            account_id: CHARLIE,
+            native_fee_amount: 18,
+        }
+        .into()]);
+    });
+}
+
+#[test]
+fn sponsor_should_pay_fee_in_its_account_currency() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .with_currencies(vec![(ALICE, SUPPORTED_CURRENCY)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(None, balances_calls(), 20, 100, 10))
+            ));
+
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+            assert_eq!(
+                Currencies::free_balance(SUPPORTED_CURRENCY, &ALICE),
+                INITIAL_BALANCE - 30
+            );
+
+            assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+                Some(pre),
+                &info,
+                &post_info_from_weight(Weight::from_ref_time(3)),
+                len,
+                &Ok(())
+            ));
+            assert_eq!(
+                Currencies::free_balance(SUPPORTED_CURRENCY, &ALICE),
+                INITIAL_BALANCE - 27
+            );
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY, &FEE_RECEIVER), 27);
+        });
+}
+
+#[test]
+fn signer_should_pay_fee_when_no_rule_matches() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_native_balance(CHARLIE, 1_000)
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            let other_pallet_calls = vec![SponsoredCall {
+                pallet_index: BALANCES_PALLET_INDEX,
+                call_index: Some(1),
+            }];
+
+            for rule in [
+                // other caller
+                sponsorship_rule(Some(vec![BOB]), balances_calls(), 20, 100, 10),
+                // other call
+                sponsorship_rule(Some(vec![CHARLIE]), other_pallet_calls, 20, 100, 10),
+                // fee too high
+                sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 19, 100, 10),
+                // budget too low
+                sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 20, 19, 10),
+            ] {
+                assert_ok!(PaymentPallet::set_sponsorship(RuntimeOrigin::signed(ALICE), Some(rule)));
+
+                let pre = ChargeTransactionPayment::<Test>::from(0)
+                    .pre_dispatch(&CHARLIE, CALL, &info, len)
+                    .unwrap();
+                assert_eq!(pre.2, Some(PaymentInfo::Native(20)));
+            }
+
+            assert_eq!(
+                Balances::free_balance(ALICE),
+                INITIAL_BALANCE - SponsorshipDeposit::get()
+            );
+            assert_eq!(Balances::free_balance(CHARLIE), 1_000 - 4 * 20);
+        });
+}
+
+#[test]
+fn sponsorship_budget_should_be_renewed_every_period() {
+    ExtBuilder::default().base_weight(5).build().execute_with(|| {
+        let len = 10;
+        let info = info_from_weight(Weight::from_ref_time(5));
+        assert_ok!(PaymentPallet::set_sponsorship(
+            RuntimeOrigin::signed(ALICE),
+            Some(sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 20, 40, 10))
+        ));
+
+        for _ in 0..2 {
+            assert_ok!(ChargeTransactionPayment::<Test>::from(0).pre_dispatch(&CHARLIE, CALL, &info, len));
+        }
+        // budget exhausted and Charlie can't pay
+        assert!(ChargeTransactionPayment::<Test>::from(0)
+            .pre_dispatch(&CHARLIE, CALL, &info, len)
+            .is_err());
+
+        System::set_block_number(10);
+        assert_ok!(ChargeTransactionPayment::<Test>::from(0).pre_dispatch(&CHARLIE, CALL, &info, len));
+        assert_eq!(PaymentPallet::sponsorship_spending(ALICE), (10, 20));
+        assert_eq!(
+            Balances::free_balance(ALICE),
+            INITIAL_BALANCE - SponsorshipDeposit::get() - 60
+        );
+    });
+}
+
+#[test]
+fn signer_should_pay_fee_when_sponsor_cannot_pay() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_native_balance(CHARLIE, 1_000)
+        .with_currencies(vec![(ALICE, SUPPORTED_CURRENCY_NO_BALANCE)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 20, 100, 10))
+            ));
+
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+
+            assert_eq!(pre.2, Some(PaymentInfo::Native(20)));
+            assert_eq!(Balances::free_balance(CHARLIE), 1_000 - 20);
+            assert_eq!(Currencies::free_balance(SUPPORTED_CURRENCY_NO_BALANCE, &ALICE), 0);
+            assert_eq!(PaymentPallet::sponsorship_spending(ALICE), (0, 0));
+        });
+}
+
+#[test]
+fn sponsor_of_call_should_be_checked_before_sponsor_of_pallet() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_native_balance(BOB, 10_000)
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            let transfer_calls = vec![SponsoredCall {
+                pallet_index: BALANCES_PALLET_INDEX,
+                call_index: Some(0),
+            }];
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(None, balances_calls(), 20, 100, 10))
+            ));
+
+            // rule of the call sponsor does not match
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(BOB),
+                Some(sponsorship_rule(Some(vec![ALICE]), transfer_calls.clone(), 20, 100, 10))
+            ));
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+            assert!(matches!(pre.2, Some(PaymentInfo::Sponsored { sponsor: ALICE, .. })));
+
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(BOB),
+                Some(sponsorship_rule(None, transfer_calls, 20, 100, 10))
+            ));
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+            assert!(matches!(pre.2, Some(PaymentInfo::Sponsored { sponsor: BOB, .. })));
+        });
+}
+
+#[test]
+fn signer_should_pay_fee_and_tip_of_transaction_with_tip() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_native_balance(CHARLIE, 1_000)
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(Some(vec![CHARLIE]), balances_calls(), 100, 1_000, 10))
+            ));
+
+            let pre = ChargeTransactionPayment::<Test>::from(5)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+
+            assert_eq!(pre.2, Some(PaymentInfo::Native(25)));
+            assert_eq!(Balances::free_balance(CHARLIE), 1_000 - 25);
+            assert_eq!(
+                Balances::free_balance(ALICE),
+                INITIAL_BALANCE - SponsorshipDeposit::get()
+            );
+            assert_eq!(PaymentPallet::sponsorship_spending(ALICE), (0, 0));
+        });
+}
+
+#[test]
+fn sponsor_lookup_should_not_register_extra_weight() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_native_balance(CHARLIE, 1_000)
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_sponsorship(
+                RuntimeOrigin::signed(ALICE),
+                Some(sponsorship_rule(Some(vec![BOB]), balances_calls(), 20, 100, 10))
+            ));
+
+            assert_ok!(ChargeTransactionPayment::<Test>::from(0).pre_dispatch(&CHARLIE, CALL, &info, len));
+            assert_eq!(System::block_weight().total(), Weight::zero());
+        });
+}
+
+#[test]
+fn set_fallback_currencies_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            PaymentPallet::set_fallback_currencies(RuntimeOrigin::signed(ALICE), Some(vec![SUPPORTED_CURRENCY])),
+            BadOrigin
+        );
+        assert_noop!(
+            PaymentPallet::set_fallback_currencies(
+                RuntimeOrigin::root(),
+                Some(vec![SUPPORTED_CURRENCY, UNSUPPORTED_CURRENCY])
+            ),
+            Error::<Test>::UnsupportedCurrency
+        );
+        assert_noop!(
+            PaymentPallet::set_fallback_currencies(
+                RuntimeOrigin::root(),
+                Some(vec![
+                    SUPPORTED_CURRENCY,
+                    SUPPORTED_CURRENCY_WITH_PRICE,
+                    HIGH_ED_CURRENCY
+                ])
+            ),
+            Error::<Test>::TooManyFallbackCurrencies
+        );
+
+        assert_ok!(PaymentPallet::set_fallback_currencies(
+            RuntimeOrigin::root(),
+            Some(vec![SUPPORTED_CURRENCY])
+        ));
+        assert_eq!(
+            PaymentPallet::fallback_currencies().map(|c| c.into_inner()),
+            Some(vec![SUPPORTED_CURRENCY])
+        );
+        expect_events(vec![Event::FallbackCurrenciesSet {
+            currencies: Some(vec![SUPPORTED_CURRENCY].try_into().unwrap()),
+        }
+        .into()]);
+
+        assert_ok!(PaymentPallet::set_fallback_currencies(RuntimeOrigin::root(), None));
+        assert_eq!(PaymentPallet::fallback_currencies(), None);
+    });
+}
+
+#[test]
+fn fee_should_not_fall_back_when_fallback_is_disabled() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .with_currencies(vec![(ALICE, SUPPORTED_CURRENCY_NO_BALANCE)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+
+            assert!(ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&ALICE, CALL, &info, len)
+                .is_err());
+        });
+}
+
+#[test]
+fn fee_should_fall_back_to_native_currency() {
+    ExtBuilder::default()
+        .base_weight(5)
+        .with_currencies(vec![(ALICE, SUPPORTED_CURRENCY_NO_BALANCE)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_fallback_currencies(
+                RuntimeOrigin::root(),
+                Some(vec![SUPPORTED_CURRENCY])
+            ));
+
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&ALICE, CALL, &info, len)
+                .unwrap();
+
+            assert_eq!(pre.2, Some(PaymentInfo::Native(20)));
+            assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - 20);
+            expect_events(vec![Event::FeeCurrencyFallback {
+                account_id: ALICE,
+                requested_asset_id: SUPPORTED_CURRENCY_NO_BALANCE,
+                used_asset_id: HDX,
+            }
+            .into()]);
+            // account currency is not changed
+            assert_eq!(PaymentPallet::get_currency(ALICE), Some(SUPPORTED_CURRENCY_NO_BALANCE));
+        });
+}
+
+#[test]
+fn fee_should_fall_back_to_fallback_currencies_in_order() {
+    const CHARLIE: AccountId = 5;
+
+    ExtBuilder::default()
+        .base_weight(5)
+        .account_tokens(CHARLIE, SUPPORTED_CURRENCY, 10_000)
+        .account_tokens(CHARLIE, SUPPORTED_CURRENCY_NO_BALANCE, 10)
+        .with_currencies(vec![(CHARLIE, SUPPORTED_CURRENCY_NO_BALANCE)])
+        .build()
+        .execute_with(|| {
+            let len = 10;
+            let info = info_from_weight(Weight::from_ref_time(5));
+            assert_ok!(PaymentPallet::set_fallback_currencies(
+                RuntimeOrigin::root(),
+                Some(vec![SUPPORTED_CURRENCY_WITH_PRICE, SUPPORTED_CURRENCY])
+            ));
+
+            let pre = ChargeTransactionPayment::<Test>::from(0)
+                .pre_dispatch(&CHARLIE, CALL, &info, len)
+                .unwrap();
+
+            assert_eq!(
+                pre.2,
+                Some(PaymentInfo::NonNative(30, SUPPORTED_CURRENCY, Price::from_float(1.5)))
+            );
+            assert_eq!(Tokens::free_balance(SUPPORTED_CURRENCY, &CHARLIE), 10_000 - 30);
+            assert_eq!(Tokens::free_balance(SUPPORTED_CURRENCY_NO_BALANCE, &CHARLIE), 10);
+            expect_events(vec![Event::FeeCurrencyFallback {
+                account_id: CHARLIE,
+                requested_asset_id: SUPPORTED_CURRENCY_NO_BALANCE,
+                used_asset_id: SUPPORTED_CURRENCY,
+            }
+            .into()]);
+        });
+}
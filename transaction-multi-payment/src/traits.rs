@@ -1,12 +1,37 @@
-use frame_support::sp_runtime::DispatchResult;
+use frame_support::sp_runtime::{
+    traits::DispatchInfoOf, transaction_validity::TransactionValidityError, DispatchResult,
+};
+use pallet_transaction_payment::OnChargeTransaction;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum PaymentInfo<Balance, AssetId, Price> {
+pub enum PaymentInfo<Balance, AssetId, Price, AccountId> {
     Native(Balance),
     NonNative(Balance, AssetId, Price),
+    /// Fee paid by a sponsor. `native_fee` is the fee in native currency, `fee` the amount withdrawn in `currency`.
+    Sponsored {
+        sponsor: AccountId,
+        native_fee: Balance,
+        fee: Balance,
+        currency: AssetId,
+        price: Price,
+    },
 }
 
 /// Handler for dealing with fees
 pub trait DepositFee<AccountId, AssetId, Balance> {
     fn deposit_fee(who: &AccountId, currency: AssetId, amount: Balance) -> DispatchResult;
 }
+
+/// Handler for withdrawing transaction fees in a currency chosen for a single transaction
+pub trait WithdrawFeeInCurrency<T: pallet_transaction_payment::Config, AssetId>: OnChargeTransaction<T> {
+    /// Withdraw the predicted fee like `OnChargeTransaction::withdraw_fee`, but in `currency` instead of the account
+    /// currency.
+    fn withdraw_fee_in_currency(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        info: &DispatchInfoOf<T::RuntimeCall>,
+        fee: Self::Balance,
+        tip: Self::Balance,
+        currency: AssetId,
+    ) -> Result<Self::LiquidityInfo, TransactionValidityError>;
+}
@@ -35,6 +35,7 @@
 // --output=paymenta.rs
 // --extrinsic=*
 
+// NOTE: The weights of `set_sponsorship` and `find_sponsor` are placeholders until they are benchmarked.
 #![allow(unused_parens)]
 #![allow(unused_imports)]
 #![allow(clippy::unnecessary_cast)]
@@ -51,6 +52,9 @@ pub trait WeightInfo {
     fn set_currency() -> Weight;
     fn add_currency() -> Weight;
     fn remove_currency() -> Weight;
+    fn set_sponsorship(c: u32) -> Weight;
+    fn set_fallback_currencies() -> Weight;
+    fn find_sponsor() -> Weight;
 }
 
 pub struct BasiliskWeight<T>(PhantomData<T>);
@@ -76,6 +80,24 @@ impl<T: frame_system::Config> WeightInfo for BasiliskWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as u64))
             .saturating_add(T::DbWeight::get().writes(1 as u64))
     }
+    fn set_sponsorship(c: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(38_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(5_000_000 as u64).saturating_mul(c as u64))
+            .saturating_add(T::DbWeight::get().reads(4 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(c as u64)))
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+    }
+    fn set_fallback_currencies() -> Weight {
+        Weight::from_ref_time(16_845_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn find_sponsor() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(22_000_000 as u64).saturating_add(T::DbWeight::get().reads(7 as u64))
+    }
 }
 
 // For backwards compatibility and tests
@@ -100,4 +122,22 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as u64))
             .saturating_add(RocksDbWeight::get().writes(1 as u64))
     }
+    fn set_sponsorship(c: u32) -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(38_000_000 as u64)
+            .saturating_add(Weight::from_ref_time(5_000_000 as u64).saturating_mul(c as u64))
+            .saturating_add(RocksDbWeight::get().reads(4 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(c as u64)))
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+    }
+    fn set_fallback_currencies() -> Weight {
+        Weight::from_ref_time(16_845_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn find_sponsor() -> Weight {
+        // placeholder, not benchmarked
+        Weight::from_ref_time(22_000_000 as u64).saturating_add(RocksDbWeight::get().reads(7 as u64))
+    }
 }